@@ -0,0 +1,149 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, Ident, Token};
+
+/// Implement `Inspect` for a component.
+/// Only fields with an `#[inspect(min = .., max = ..)]` attribute are shown,
+/// clamped to that inclusive range. A `#[inspect(validate = path)]` on the
+/// struct names a function called after every change.
+#[proc_macro_derive(Inspect, attributes(inspect))]
+pub fn derive_inspect(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// A `name = value` argument of an `#[inspect(..)]` attribute.
+struct Arg {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Self { name, value })
+    }
+}
+
+/// Arguments of every `#[inspect(..)]` attribute in `attrs`.
+fn args(attrs: &[Attribute]) -> syn::Result<Vec<Arg>> {
+    let mut args = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("inspect")) {
+        args.extend(attr.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?);
+    }
+    Ok(args)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "Inspect needs named fields")),
+        },
+        _ => return Err(Error::new_spanned(input, "Inspect only supports structs")),
+    };
+
+    let mut validate = None;
+    for arg in args(&input.attrs)? {
+        if arg.name != "validate" {
+            return Err(Error::new_spanned(arg.name, "expected `validate`"));
+        }
+        validate = Some(arg.value);
+    }
+
+    let (mut names, mut idents, mut types, mut mins, mut maxs) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for field in fields {
+        let args = args(&field.attrs)?;
+        if args.is_empty() {
+            continue;
+        }
+
+        let (mut min, mut max) = (None, None);
+        for arg in args {
+            if arg.name == "min" {
+                min = Some(arg.value);
+            } else if arg.name == "max" {
+                max = Some(arg.value);
+            } else {
+                return Err(Error::new_spanned(arg.name, "expected `min` or `max`"));
+            }
+        }
+        let (min, max) = match (min, max) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return Err(Error::new_spanned(field, "expected both `min` and `max`")),
+        };
+
+        let ident = field.ident.clone().unwrap();
+        names.push(ident.to_string());
+        idents.push(ident);
+        types.push(&field.ty);
+        mins.push(min);
+        maxs.push(max);
+    }
+
+    let validate = validate.map(|validate| {
+        quote! {
+            fn validate(&mut self, owner: &str) {
+                #validate(self, owner)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl crate::inspector::Inspect for #name {
+            fn fields(&self) -> &'static [crate::inspector::Field] {
+                const FIELDS: &[crate::inspector::Field] = &[#(crate::inspector::Field {
+                    name: #names,
+                    variant_type: <#types as crate::inspector::FieldValue>::VARIANT_TYPE,
+                    min: (#mins) as f64,
+                    max: (#maxs) as f64,
+                }),*];
+                FIELDS
+            }
+
+            fn get_field(&self, name: &str) -> Option<::gdnative::prelude::Variant> {
+                match name {
+                    #(#names => Some(::gdnative::prelude::ToVariant::to_variant(&self.#idents)),)*
+                    _ => None,
+                }
+            }
+
+            fn set_field(
+                &mut self,
+                name: &str,
+                value: &::gdnative::prelude::Variant,
+                owner: &str,
+            ) -> bool {
+                match name {
+                    #(#names => {
+                        self.#idents = crate::inspector::clamp_field(
+                            owner,
+                            #names,
+                            value,
+                            self.#idents,
+                            (#mins) as f64,
+                            (#maxs) as f64,
+                        );
+                        true
+                    })*
+                    _ => false,
+                }
+            }
+
+            fn default_field(&self, name: &str) -> Option<::gdnative::prelude::Variant> {
+                crate::inspector::Inspect::get_field(&<Self as Default>::default(), name)
+            }
+
+            #validate
+        }
+    })
+}
@@ -0,0 +1,212 @@
+use bevy::app::Events;
+use bevy::prelude::{
+    App, Component, Entity, EventReader, EventWriter, ParallelSystemDescriptorCoercion, Plugin,
+    Query, Res,
+};
+use gdnative::api::{
+    AnimatedSprite, AnimationNode, AnimationNodeAnimation, AnimationNodeBlendSpace1D,
+    AnimationNodeBlendSpace2D, AnimationNodeStateMachine, AnimationNodeStateMachinePlayback,
+    AnimationPlayer, AnimationTree,
+};
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::registry::NodeRegistry;
+use crate::components::Velocity;
+
+/// Sent by `AnimationPlayer` method tracks, e.g. "hitbox_on" or "footstep".
+pub struct AnimationEvent {
+    pub entity: Entity,
+    pub name: String,
+}
+
+/// An animation event as received from Godot, keyed by instance id.
+pub struct NodeAnimationEvent {
+    source: i64,
+    name: String,
+}
+
+/// Forward an animation event of the node with `source` instance id into the ECS.
+/// Called from the `animation_event` method of scripts, which method tracks call.
+pub fn send_animation_event(source: i64, name: String) {
+    with_world(|w| {
+        if let Some(mut events) = w.get_resource_mut::<Events<NodeAnimationEvent>>() {
+            events.send(NodeAnimationEvent { source, name });
+        }
+    });
+}
+
+/// Animation Event System.
+/// Translates animation events from instance ids to entities.
+pub fn animation_event_system(
+    mut node_events: EventReader<NodeAnimationEvent>,
+    registry: Res<NodeRegistry>,
+    mut events: EventWriter<AnimationEvent>,
+) {
+    for NodeAnimationEvent { source, name } in node_events.iter() {
+        if let Some(entity) = registry.entity(*source) {
+            events.send(AnimationEvent {
+                entity,
+                name: name.clone(),
+            });
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Target {
+    Tree {
+        tree: Ref<AnimationTree>,
+        playback: Ref<AnimationNodeStateMachinePlayback>,
+        player: Ref<AnimationPlayer>,
+    },
+    Sprite(Ref<AnimatedSprite>),
+}
+
+/// Animator Component.
+/// Plays named animations on an `AnimationTree` state machine or an
+/// `AnimatedSprite`, and knows how long they are.
+#[derive(Component, Clone)]
+pub struct Animator {
+    target: Target,
+    current: Option<&'static str>,
+}
+
+impl Animator {
+    /// `None`, with an error, if the tree has no state machine playback or
+    /// animation player.
+    pub fn tree(tree: TRef<AnimationTree>) -> Option<Self> {
+        let playback = tree
+            .get("parameters/playback")
+            .try_to_object::<AnimationNodeStateMachinePlayback>();
+        let player = tree
+            .get_node(tree.animation_player())
+            .and_then(|player| player.try_safe())
+            .and_then(|player| player.cast::<AnimationPlayer>());
+
+        match (playback, player) {
+            (Some(playback), Some(player)) => Some(Self {
+                target: Target::Tree {
+                    tree: tree.claim(),
+                    playback,
+                    player: player.claim(),
+                },
+                current: None,
+            }),
+            (None, _) => {
+                godot_error!("{}: no state machine playback", tree.name());
+                None
+            }
+            (_, None) => {
+                godot_error!("{}: no AnimationPlayer", tree.name());
+                None
+            }
+        }
+    }
+
+    pub fn sprite(sprite: TRef<AnimatedSprite>) -> Self {
+        Self {
+            target: Target::Sprite(sprite.claim()),
+            current: None,
+        }
+    }
+
+    /// Travel to the state `name`, or play the sprite animation `name`.
+    pub fn play(&mut self, name: &'static str) {
+        if self.current == Some(name) {
+            return;
+        }
+
+        match &self.target {
+            Target::Tree { tree, playback, .. } => {
+                if tree.try_safe().is_none() {
+                    return;
+                }
+                match playback.try_safe() {
+                    Some(playback) => playback.travel(name),
+                    None => return,
+                }
+            }
+            Target::Sprite(sprite) => match sprite.try_safe() {
+                Some(sprite) => sprite.play(name, false),
+                None => return,
+            },
+        }
+        self.current = Some(name);
+    }
+
+    /// Mirror a sprite horizontally. Trees pick directions through blend
+    /// positions instead.
+    pub fn set_flip_h(&self, flip: bool) {
+        if let Target::Sprite(sprite) = &self.target {
+            if let Some(sprite) = sprite.try_safe() {
+                sprite.set_flip_h(flip);
+            }
+        }
+    }
+
+    /// Length in seconds of the animation `name`.
+    /// States of a tree use the clip of their first blend point.
+    pub fn length(&self, name: &str) -> Option<f32> {
+        match &self.target {
+            Target::Tree { tree, player, .. } => {
+                let root = tree.try_safe()?.tree_root()?;
+                let machine = root.try_safe()?.cast::<AnimationNodeStateMachine>()?;
+                let node = machine.get_node(name)?;
+                let clip = clip(node.try_safe()?)?;
+                let animation = player.try_safe()?.get_animation(clip)?;
+                Some(animation.try_safe()?.length() as f32)
+            }
+            Target::Sprite(sprite) => {
+                let frames = sprite.try_safe()?.sprite_frames()?;
+                let frames = frames.try_safe()?;
+                let speed = frames.get_animation_speed(name);
+                if speed <= 0. {
+                    return None;
+                }
+                Some((frames.get_frame_count(name) as f64 / speed) as f32)
+            }
+        }
+    }
+}
+
+/// Name of the clip an animation tree node plays.
+fn clip(node: TRef<AnimationNode>) -> Option<GodotString> {
+    if let Some(animation) = node.cast::<AnimationNodeAnimation>() {
+        return Some(animation.animation());
+    }
+    let point = if let Some(blend) = node.cast::<AnimationNodeBlendSpace2D>() {
+        blend.get_blend_point_node(0)
+    } else if let Some(blend) = node.cast::<AnimationNodeBlendSpace1D>() {
+        blend.get_blend_point_node(0)
+    } else {
+        None
+    };
+    let point = point?;
+    clip(point.try_safe()?.upcast())
+}
+
+/// A state that maps to an animation.
+pub trait AnimationState {
+    fn animation(&self, velocity: Vector2) -> &'static str;
+}
+
+/// Animation System.
+/// Plays the animation of each entity's current state.
+pub fn animation_system<S: AnimationState + Component>(
+    mut query: Query<(&S, &Velocity, &mut Animator)>,
+) {
+    for (state, velocity, mut animator) in query.iter_mut() {
+        animator.play(state.animation(velocity.velocity));
+    }
+}
+
+pub struct AnimationPlugin;
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NodeAnimationEvent>()
+            .add_event::<AnimationEvent>()
+            .add_system(animation_event_system.label("animation_event"));
+    }
+}
@@ -0,0 +1,112 @@
+use std::any::type_name;
+
+use bevy::prelude::{Bundle, Component, Entity, World};
+use gdnative::export::user_data::Map;
+use gdnative::prelude::*;
+use gdrust::ecs::engine_sync::components::{GodotObjRef, PlayingGame};
+
+use crate::bridge::registry::register_node;
+
+/// Godot Entity Builder.
+/// Spawns the entity backed by a bundle's owner node and collects components
+/// from its children. A missing child is logged with its node path instead
+/// of panicking, and the entity is spawned without that component.
+pub struct GodotEntity<'w, 'a, T: GodotObject + SubClass<Node>> {
+    world: &'w mut World,
+    entity: Entity,
+    owner: TRef<'a, T>,
+}
+
+impl<'w, 'a, T: GodotObject + SubClass<Node>> GodotEntity<'w, 'a, T> {
+    /// Spawn a new entity backed by `owner`.
+    pub fn spawn(world: &'w mut World, owner: TRef<'a, T>) -> Self {
+        let entity = world.spawn().insert(PlayingGame).id();
+        Self::attach(world, entity, owner)
+    }
+
+    /// Back the existing `entity` with `owner`, e.g. an entity that outlived
+    /// the scene of its previous node.
+    pub fn attach(world: &'w mut World, entity: Entity, owner: TRef<'a, T>) -> Self {
+        register_node(world, entity, owner);
+
+        Self {
+            world,
+            entity,
+            owner,
+        }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn insert(self, component: impl Component) -> Self {
+        self.world.entity_mut(self.entity).insert(component);
+        self
+    }
+
+    pub fn insert_bundle(self, bundle: impl Bundle) -> Self {
+        self.world.entity_mut(self.entity).insert_bundle(bundle);
+        self
+    }
+
+    /// Insert the script instance of the child at `path` as a component.
+    pub fn child<C>(self, path: &str) -> Self
+    where
+        C: NativeClass + Component + Clone,
+        C::Base: SubClass<Node>,
+        C::UserData: Map,
+    {
+        let component = self.get_node::<C::Base>(path).and_then(|node| {
+            let component = node.cast_instance::<C>()?.map(|c, _| c.clone()).ok()?;
+            Some((node, component))
+        });
+
+        match component {
+            Some((node, component)) => {
+                register_node(self.world, self.entity, node);
+                self.insert(component)
+            }
+            None => self.missing(path, type_name::<C>()),
+        }
+    }
+
+    /// Insert a component built from the child node at `path`.
+    pub fn node<N, C>(self, path: &str, build: impl FnOnce(TRef<'a, N>) -> C) -> Self
+    where
+        N: GodotObject + SubClass<Node>,
+        C: Component,
+    {
+        match self.get_node::<N>(path) {
+            Some(node) => {
+                register_node(self.world, self.entity, node);
+                self.insert(build(node))
+            }
+            None => self.missing(path, N::class_name()),
+        }
+    }
+
+    /// Insert the child node at `path` as a `GodotObjRef`.
+    pub fn node_ref<N>(self, path: &str) -> Self
+    where
+        N: GodotObject + SubClass<Node>,
+        GodotObjRef<N>: Component,
+    {
+        self.node::<N, _>(path, |node| GodotObjRef::new(node.claim()))
+    }
+
+    fn get_node<N: GodotObject + SubClass<Node>>(&self, path: &str) -> Option<TRef<'a, N>> {
+        let node = self.owner.upcast::<Node>().get_node(path)?;
+        unsafe { node.assume_safe() }.cast::<N>()
+    }
+
+    fn missing(self, path: &str, provides: &str) -> Self {
+        godot_error!(
+            "{}: missing child node `{}` providing `{}`",
+            self.owner.upcast::<Node>().name(),
+            path,
+            provides,
+        );
+        self
+    }
+}
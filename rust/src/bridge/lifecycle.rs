@@ -0,0 +1,119 @@
+use bevy::prelude::{Commands, DespawnRecursiveExt, ResMut, World};
+use gdnative::object::memory::{ManuallyManaged, RefCounted};
+use gdnative::prelude::*;
+
+use crate::bridge::registry::NodeRegistry;
+use crate::bridge::SIGNAL_BRIDGE_PATH;
+
+/// Validated access to nodes that may be freed outside the ECS.
+pub trait SafeRefExt<T: GodotObject> {
+    /// Like `expect_safe`, but logs an error and returns `None` instead of
+    /// panicking when the node has already been freed.
+    fn try_safe<'a>(&self) -> Option<TRef<'a, T>>;
+}
+
+impl<T> SafeRefExt<T> for Ref<T, Shared>
+where
+    T: GodotObject,
+    T::Memory: SafeMemory,
+{
+    fn try_safe<'a>(&self) -> Option<TRef<'a, T>> {
+        <T::Memory as SafeMemory>::try_safe(self)
+    }
+}
+
+/// How references are validated, by memory management kind.
+pub trait SafeMemory: Sized {
+    fn try_safe<'a, T: GodotObject<Memory = Self>>(object: &Ref<T, Shared>) -> Option<TRef<'a, T>>;
+}
+
+impl SafeMemory for ManuallyManaged {
+    fn try_safe<'a, T: GodotObject<Memory = Self>>(object: &Ref<T, Shared>) -> Option<TRef<'a, T>> {
+        let node = unsafe { object.assume_safe_if_sane() };
+        if node.is_none() {
+            godot_error!("Dangling reference to a freed {}", T::class_name());
+        }
+        node
+    }
+}
+
+impl SafeMemory for RefCounted {
+    /// A reference keeps its object alive, so it is always valid.
+    fn try_safe<'a, T: GodotObject<Memory = Self>>(object: &Ref<T, Shared>) -> Option<TRef<'a, T>> {
+        Some(unsafe { object.assume_safe() })
+    }
+}
+
+/// The scene tree, for systems that have no node at hand.
+pub fn scene_tree<'a>() -> Option<TRef<'a, SceneTree>> {
+    Engine::godot_singleton()
+        .get_main_loop()
+        .and_then(|main_loop| main_loop.try_safe())
+        .and_then(|main_loop| main_loop.cast::<SceneTree>())
+}
+
+/// The SignalBridge autoload as seen from `node`, logging an error if it is
+/// missing or `node` is outside the scene tree.
+pub fn signal_bridge<'a>(node: &Node) -> Option<TRef<'a, Node>> {
+    let bridge = node
+        .get_node(SIGNAL_BRIDGE_PATH)
+        .and_then(|bridge| bridge.try_safe());
+    if bridge.is_none() {
+        godot_error!("{}: no SignalBridge at {}", node.name(), SIGNAL_BRIDGE_PATH);
+    }
+    bridge
+}
+
+/// Despawn the entity of `node` once it is freed after leaving the scene
+/// tree.
+pub fn watch_tree_exiting(node: TRef<Node>) {
+    let bridge = match signal_bridge(&node) {
+        Some(bridge) => bridge,
+        None => return,
+    };
+    let binds = VariantArray::new();
+    binds.push(node);
+    binds.push(node.get_instance_id());
+
+    if let Err(err) = node.connect(
+        "tree_exiting",
+        bridge,
+        "_on_tree_exiting",
+        binds.into_shared(),
+        0,
+    ) {
+        godot_error!("Could not connect tree_exiting to SignalBridge: {:?}", err);
+    }
+}
+
+/// Exiting Nodes Resource.
+/// Nodes that left the scene tree since the last frame. Nodes that were
+/// moved or only removed are still alive a frame later, freed ones are not.
+#[derive(Default)]
+pub struct ExitingNodes(Vec<(i64, Ref<Node>)>);
+
+/// Remember that `node` with `instance_id` left the scene tree.
+pub fn node_exiting(w: &mut World, instance_id: i64, node: Ref<Node>) {
+    w.get_resource_or_insert_with(ExitingNodes::default)
+        .0
+        .push((instance_id, node));
+}
+
+/// Node Exit System.
+/// Forgets freed nodes and despawns the entities they backed, together with
+/// every other node registered for those entities.
+pub fn node_exit_system(
+    mut commands: Commands,
+    mut exiting: ResMut<ExitingNodes>,
+    mut registry: ResMut<NodeRegistry>,
+) {
+    for (instance_id, node) in exiting.0.drain(..) {
+        if unsafe { node.assume_safe_if_sane() }.is_some() {
+            continue;
+        }
+        if let Some(entity) = registry.remove_node(instance_id) {
+            registry.remove_entity(entity);
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
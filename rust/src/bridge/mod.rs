@@ -0,0 +1,194 @@
+use bevy::app::Events;
+use bevy::prelude::{
+    App, CoreStage, Entity, EventReader, EventWriter, ParallelSystemDescriptorCoercion, Plugin, Res,
+};
+use gdnative::api::Area2D;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::lifecycle::{
+    node_exit_system, node_exiting, signal_bridge, ExitingNodes, SafeRefExt,
+};
+use crate::bridge::registry::{registry_cleanup_system, NodeRegistry};
+use crate::bridge::spatial::{spatial_grid_system, SpatialGrid};
+
+pub mod builder;
+pub mod lifecycle;
+pub mod registry;
+pub mod spatial;
+
+/// Path of the autoloaded SignalBridge node.
+pub(crate) const SIGNAL_BRIDGE_PATH: &str = "/root/SignalBridge";
+
+/// Area Entered Event.
+/// `a` owns the area that emitted `area_entered`, `b` owns the area that entered it.
+#[derive(Clone, Copy)]
+pub struct AreaEntered {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Area Exited Event.
+/// `a` owns the area that emitted `area_exited`, `b` owns the area that left it.
+#[derive(Clone, Copy)]
+pub struct AreaExited {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Body Entered Event.
+/// `a` owns the area that emitted `body_entered`, `b` owns the body that entered it.
+#[derive(Clone, Copy)]
+pub struct BodyEntered {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Body Exited Event.
+/// `a` owns the area that emitted `body_exited`, `b` owns the body that left it.
+#[derive(Clone, Copy)]
+pub struct BodyExited {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+#[derive(Clone, Copy)]
+enum SignalKind {
+    AreaEntered,
+    AreaExited,
+    BodyEntered,
+    BodyExited,
+}
+
+/// A Godot signal as received by the bridge, keyed by instance id.
+#[derive(Clone, Copy)]
+pub struct NodeSignal {
+    kind: SignalKind,
+    source: i64,
+    other: i64,
+}
+
+/// Signal Bridge.
+/// Autoloaded node that receives signals from ECS-backed nodes and
+/// forwards them into the ECS world.
+#[gdrust(extends = Node)]
+#[derive(Default, Clone)]
+pub struct SignalBridge;
+
+#[methods]
+impl SignalBridge {
+    #[export]
+    fn _on_area_entered(&self, _owner: TRef<Node>, area: Ref<Area2D>, source: i64) {
+        if let Some(area) = area.try_safe() {
+            send_signal(SignalKind::AreaEntered, source, area.get_instance_id());
+        }
+    }
+
+    #[export]
+    fn _on_area_exited(&self, _owner: TRef<Node>, area: Ref<Area2D>, source: i64) {
+        if let Some(area) = area.try_safe() {
+            send_signal(SignalKind::AreaExited, source, area.get_instance_id());
+        }
+    }
+
+    #[export]
+    fn _on_body_entered(&self, _owner: TRef<Node>, body: Ref<Node>, source: i64) {
+        if let Some(body) = body.try_safe() {
+            send_signal(SignalKind::BodyEntered, source, body.get_instance_id());
+        }
+    }
+
+    #[export]
+    fn _on_body_exited(&self, _owner: TRef<Node>, body: Ref<Node>, source: i64) {
+        if let Some(body) = body.try_safe() {
+            send_signal(SignalKind::BodyExited, source, body.get_instance_id());
+        }
+    }
+
+    #[export]
+    fn _on_tree_exiting(&self, _owner: TRef<Node>, node: Ref<Node>, source: i64) {
+        with_world(|w| node_exiting(w, source, node));
+    }
+}
+
+fn send_signal(kind: SignalKind, source: i64, other: i64) {
+    with_world(|w| {
+        if let Some(mut events) = w.get_resource_mut::<Events<NodeSignal>>() {
+            events.send(NodeSignal {
+                kind,
+                source,
+                other,
+            });
+        }
+    });
+}
+
+/// Connect the enter/exit signals of `area` to the SignalBridge.
+pub fn connect_area_signals(area: TRef<Area2D>) {
+    let bridge = match signal_bridge(&area) {
+        Some(bridge) => bridge,
+        None => return,
+    };
+    let source = area.get_instance_id();
+
+    for (signal, method) in [
+        ("area_entered", "_on_area_entered"),
+        ("area_exited", "_on_area_exited"),
+        ("body_entered", "_on_body_entered"),
+        ("body_exited", "_on_body_exited"),
+    ] {
+        let binds = VariantArray::new();
+        binds.push(source);
+
+        if let Err(err) = area.connect(signal, bridge, method, binds.into_shared(), 0) {
+            godot_error!("Could not connect {} to SignalBridge: {:?}", signal, err);
+        }
+    }
+}
+
+/// Node Signal System.
+/// Translates bridged signals from instance ids to entities.
+pub fn node_signal_system(
+    mut signals: EventReader<NodeSignal>,
+    registry: Res<NodeRegistry>,
+    mut area_entered: EventWriter<AreaEntered>,
+    mut area_exited: EventWriter<AreaExited>,
+    mut body_entered: EventWriter<BodyEntered>,
+    mut body_exited: EventWriter<BodyExited>,
+) {
+    for signal in signals.iter() {
+        let (a, b) = match (
+            registry.entity(signal.source),
+            registry.entity(signal.other),
+        ) {
+            (Some(a), Some(b)) => (a, b),
+            _ => continue,
+        };
+
+        match signal.kind {
+            SignalKind::AreaEntered => area_entered.send(AreaEntered { a, b }),
+            SignalKind::AreaExited => area_exited.send(AreaExited { a, b }),
+            SignalKind::BodyEntered => body_entered.send(BodyEntered { a, b }),
+            SignalKind::BodyExited => body_exited.send(BodyExited { a, b }),
+        }
+    }
+}
+
+pub struct BridgePlugin;
+impl Plugin for BridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NodeRegistry>()
+            .init_resource::<ExitingNodes>()
+            .init_resource::<SpatialGrid>()
+            .add_event::<NodeSignal>()
+            .add_event::<AreaEntered>()
+            .add_event::<AreaExited>()
+            .add_event::<BodyEntered>()
+            .add_event::<BodyExited>()
+            .add_system(node_signal_system.label("node_signal"))
+            .add_system(spatial_grid_system.label("spatial_grid"))
+            .add_system_to_stage(CoreStage::First, node_exit_system)
+            .add_system_to_stage(CoreStage::PostUpdate, registry_cleanup_system);
+    }
+}
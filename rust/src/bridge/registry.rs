@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{Component, Entity, RemovedComponents, ResMut, World};
+use gdnative::prelude::*;
+
+use crate::bridge::lifecycle::watch_tree_exiting;
+
+/// Registered Component.
+/// Marks an entity whose nodes are tracked by the NodeRegistry.
+#[derive(Component, Default, Clone, Copy)]
+pub struct Registered;
+
+/// Node Registry Resource.
+/// Bidirectional map between Godot instance ids and the entities they back.
+/// One entity may own several nodes, e.g. a body and its hitbox/hurtbox areas.
+#[derive(Default)]
+pub struct NodeRegistry {
+    entities: HashMap<i64, Entity>,
+    nodes: HashMap<Entity, Vec<i64>>,
+}
+
+impl NodeRegistry {
+    pub fn insert(&mut self, entity: Entity, instance_id: i64) {
+        if let Some(old) = self.entities.insert(instance_id, entity) {
+            self.remove_id_of(old, instance_id);
+        }
+        self.nodes.entry(entity).or_default().push(instance_id);
+    }
+
+    /// The entity backed by the node with `instance_id`.
+    pub fn entity(&self, instance_id: i64) -> Option<Entity> {
+        self.entities.get(&instance_id).copied()
+    }
+
+    /// Instance ids of every node backing `entity`.
+    pub fn nodes(&self, entity: Entity) -> &[i64] {
+        self.nodes.get(&entity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Forget a single node, returning the entity it backed.
+    pub fn remove_node(&mut self, instance_id: i64) -> Option<Entity> {
+        let entity = self.entities.remove(&instance_id)?;
+        self.remove_id_of(entity, instance_id);
+        Some(entity)
+    }
+
+    /// Forget an entity and every node backing it.
+    pub fn remove_entity(&mut self, entity: Entity) -> Vec<i64> {
+        let ids = self.nodes.remove(&entity).unwrap_or_default();
+        for id in ids.iter() {
+            self.entities.remove(id);
+        }
+        ids
+    }
+
+    fn remove_id_of(&mut self, entity: Entity, instance_id: i64) {
+        if let Some(ids) = self.nodes.get_mut(&entity) {
+            ids.retain(|id| *id != instance_id);
+            if ids.is_empty() {
+                self.nodes.remove(&entity);
+            }
+        }
+    }
+}
+
+/// Register `node` as backing `entity`.
+/// Called from a bundle's `_ready` for the owner and every child node that
+/// emits signals or is hit by overlaps and raycasts. The entity is despawned
+/// once the node is freed.
+pub fn register_node<T>(w: &mut World, entity: Entity, node: TRef<T>)
+where
+    T: GodotObject + SubClass<Node>,
+{
+    let node = node.upcast::<Node>();
+    let instance_id = node.get_instance_id();
+
+    watch_tree_exiting(node);
+
+    w.get_resource_or_insert_with(NodeRegistry::default)
+        .insert(entity, instance_id);
+    w.entity_mut(entity).insert(Registered);
+}
+
+/// Registry Cleanup System.
+/// Forgets the nodes of despawned entities.
+pub fn registry_cleanup_system(
+    removed: RemovedComponents<Registered>,
+    mut registry: ResMut<NodeRegistry>,
+) {
+    for entity in removed.iter() {
+        registry.remove_entity(entity);
+    }
+}
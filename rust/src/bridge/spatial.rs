@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{Entity, Query, ResMut};
+use gdnative::api::Area2D;
+use gdnative::prelude::*;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::delect_box::soft_collision::SoftCollision;
+
+/// Size of a grid cell.
+/// Every indexed area fits in one cell, so looking at the neighbouring cells
+/// is enough to find every possible overlap.
+const CELL_SIZE: f32 = 32.;
+
+/// Spatial Grid Resource.
+/// A uniform grid of soft collision areas, rebuilt every frame, so overlap
+/// queries only reach across the FFI boundary for nearby pairs.
+#[derive(Default)]
+pub struct SpatialGrid {
+    cells: HashMap<(i32, i32), Vec<(Entity, Ref<Area2D>)>>,
+}
+
+impl SpatialGrid {
+    fn cell(position: Vector2) -> (i32, i32) {
+        (
+            (position.x / CELL_SIZE).floor() as i32,
+            (position.y / CELL_SIZE).floor() as i32,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.values_mut().for_each(Vec::clear);
+    }
+
+    pub fn insert(&mut self, entity: Entity, area: TRef<Area2D>) {
+        self.cells
+            .entry(Self::cell(area.global_position()))
+            .or_default()
+            .push((entity, area.claim()));
+    }
+
+    /// Entities in the cell of `position` and its eight neighbours.
+    pub fn nearby(&self, position: Vector2) -> impl Iterator<Item = &(Entity, Ref<Area2D>)> + '_ {
+        let (x, y) = Self::cell(position);
+
+        (x - 1..=x + 1)
+            .flat_map(move |x| (y - 1..=y + 1).map(move |y| (x, y)))
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    /// Indexed areas of other entities that overlap `area`.
+    pub fn overlapping<'a>(
+        &'a self,
+        entity: Entity,
+        area: TRef<'a, Area2D>,
+    ) -> impl Iterator<Item = (Entity, TRef<'a, Area2D>)> {
+        self.nearby(area.global_position())
+            .filter(move |(other, _)| *other != entity)
+            .filter_map(|(other, other_area)| Some((*other, other_area.try_safe()?)))
+            .filter(move |(_, other_area)| area.overlaps_area(*other_area))
+    }
+}
+
+/// Spatial Grid System.
+/// Buckets every soft collision area by its current position.
+pub fn spatial_grid_system(
+    mut grid: ResMut<SpatialGrid>,
+    soft_collision: Query<(Entity, &SoftCollision)>,
+) {
+    grid.clear();
+
+    for (entity, soft_collision) in soft_collision.iter() {
+        if let Some(area) = soft_collision.owner.try_safe() {
+            grid.insert(entity, area);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use bevy::prelude::{
+    App, EventReader, ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, Time, Timer,
+    With,
+};
+use gdnative::prelude::*;
+use rand::Rng;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::{BodyEntered, BodyExited};
+use crate::camera::rig::CameraRig;
+use crate::camera::room::Room;
+use crate::combat::Damaged;
+use crate::components::Stats;
+use crate::player::{Player, PlayerDied, PlayerRespawned};
+use crate::tuning::Tuning;
+
+pub mod rig;
+pub mod room;
+
+/// Trauma added per point of damage to the player.
+const PLAYER_TRAUMA: f32 = 0.5;
+/// Trauma added per point of damage to any other creature.
+const HIT_TRAUMA: f32 = 0.25;
+
+/// Points the camera at `point` for `duration` seconds.
+pub struct CameraFocus {
+    pub point: Vector2,
+    pub duration: f32,
+}
+
+/// Focus Resource.
+/// The point of interest the camera is showing instead of the player.
+#[derive(Default)]
+pub struct Focus(Option<(Vector2, Timer)>);
+
+/// Camera Trauma System.
+/// Shakes the camera when a creature takes damage.
+pub fn camera_trauma_system(
+    mut damaged: EventReader<Damaged>,
+    player: Query<(), With<Player>>,
+    creature: Query<(), With<Stats>>,
+    mut rig: Query<&mut CameraRig>,
+) {
+    for Damaged { target, amount, .. } in damaged.iter() {
+        let trauma = if player.contains(*target) {
+            PLAYER_TRAUMA
+        } else if creature.contains(*target) {
+            HIT_TRAUMA
+        } else {
+            continue;
+        };
+
+        for mut rig in rig.iter_mut() {
+            rig.add_trauma(trauma * *amount as f32);
+        }
+    }
+}
+
+/// Camera Room System.
+/// Switches the camera bounds to the room the player walks into, and back to
+/// the level bounds when the player leaves it.
+pub fn camera_room_system(
+    mut body_entered: EventReader<BodyEntered>,
+    mut body_exited: EventReader<BodyExited>,
+    room: Query<&Room>,
+    player: Query<(), With<Player>>,
+    mut rig: Query<&mut CameraRig>,
+) {
+    for BodyExited { a, b } in body_exited.iter() {
+        if let (Ok(room), true) = (room.get(*a), player.contains(*b)) {
+            for mut rig in rig.iter_mut() {
+                if rig.room_bounds == Some(room.bounds) {
+                    rig.room_bounds = None;
+                }
+            }
+        }
+    }
+    for BodyEntered { a, b } in body_entered.iter() {
+        if let (Ok(room), true) = (room.get(*a), player.contains(*b)) {
+            for mut rig in rig.iter_mut() {
+                rig.room_bounds = Some(room.bounds);
+            }
+        }
+    }
+}
+
+/// Camera Focus System.
+pub fn camera_focus_system(
+    time: Res<Time>,
+    mut requests: EventReader<CameraFocus>,
+    mut focus: ResMut<Focus>,
+) {
+    for CameraFocus { point, duration } in requests.iter() {
+        focus.0 = Some((*point, Timer::from_seconds(*duration, false)));
+    }
+
+    if let Some((_, timer)) = &mut focus.0 {
+        if timer.tick(time.delta()).finished() {
+            focus.0 = None;
+        }
+    }
+}
+
+/// Camera Death System.
+/// Holds the camera where the player died, then cuts to the respawn point
+/// instead of panning across the level.
+pub fn camera_death_system(
+    tuning: Res<Tuning>,
+    mut died: EventReader<PlayerDied>,
+    mut respawned: EventReader<PlayerRespawned>,
+    mut focus: ResMut<Focus>,
+    mut rig: Query<&mut CameraRig>,
+) {
+    for PlayerDied { position } in died.iter() {
+        let timer = Timer::from_seconds(tuning.death.respawn_delay, false);
+        focus.0 = Some((*position, timer));
+    }
+    for PlayerRespawned { position } in respawned.iter() {
+        focus.0 = None;
+        for mut rig in rig.iter_mut() {
+            rig.center = Some(*position);
+        }
+    }
+}
+
+/// Camera Follow System.
+/// Moves the camera towards the player or the focused point.
+pub fn camera_follow_system(
+    time: Res<Time>,
+    focus: Res<Focus>,
+    player: Query<&Player>,
+    mut rig: Query<&mut CameraRig>,
+) {
+    let focus = focus.0.as_ref().map(|(point, _)| *point);
+    let target = focus.or_else(|| {
+        player
+            .iter()
+            .find_map(|player| player.owner.try_safe())
+            .map(|player| player.global_position())
+    });
+    let target = match target {
+        Some(target) => target,
+        None => return,
+    };
+    let delta = time.delta_seconds();
+
+    for mut rig in rig.iter_mut() {
+        let camera = match rig.owner.try_safe() {
+            Some(camera) => camera,
+            None => continue,
+        };
+
+        let center = *rig.center.get_or_insert(target);
+        let desired = match focus {
+            Some(_) => target,
+            None => Vector2::new(
+                follow_axis(center.x, target.x, rig.deadzone.x),
+                follow_axis(center.y, target.y, rig.deadzone.y),
+            ),
+        };
+        let weight = if rig.smoothing > 0. {
+            1. - (-rig.smoothing * delta).exp()
+        } else {
+            1.
+        };
+        let mut center = center + (desired - center) * weight;
+
+        if let Some(bounds) = rig.bounds() {
+            let viewport = camera.get_viewport_rect().size;
+            let zoom = camera.zoom();
+            let half = Vector2::new(viewport.x * zoom.x, viewport.y * zoom.y) / 2.;
+            let end = bounds.position + bounds.size;
+            center.x = clamp_axis(center.x, half.x, bounds.position.x, end.x);
+            center.y = clamp_axis(center.y, half.y, bounds.position.y, end.y);
+        }
+        rig.center = Some(center);
+
+        rig.trauma = (rig.trauma - rig.trauma_decay * delta).max(0.);
+        let shake = rig.trauma * rig.trauma;
+        let mut rng = rand::thread_rng();
+        let offset = Vector2::new(
+            rig.max_shake.x * shake * rng.gen_range(-1.0..1.0),
+            rig.max_shake.y * shake * rng.gen_range(-1.0..1.0),
+        );
+
+        camera.set_global_position(center);
+        camera.set_offset(offset);
+    }
+}
+
+/// Follow `target` only once it leaves the deadzone around `center`.
+fn follow_axis(center: f32, target: f32, deadzone: f32) -> f32 {
+    if target - center > deadzone {
+        target - deadzone
+    } else if center - target > deadzone {
+        target + deadzone
+    } else {
+        center
+    }
+}
+
+/// Keep a view of half size `half` inside `min..max`, centring it when the
+/// bounds are smaller than the view.
+fn clamp_axis(center: f32, half: f32, min: f32, max: f32) -> f32 {
+    if max - min < half * 2. {
+        (min + max) / 2.
+    } else {
+        center.clamp(min + half, max - half)
+    }
+}
+
+pub struct CameraPlugin;
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .add_event::<CameraFocus>()
+            .add_system(camera_trauma_system)
+            .add_system(camera_room_system.label("camera_room").after("node_signal"))
+            .add_system(camera_focus_system.label("camera_focus"))
+            .add_system(camera_death_system.label("camera_death"))
+            .add_system(
+                camera_follow_system
+                    .after("camera_room")
+                    .after("camera_focus")
+                    .after("camera_death"),
+            );
+    }
+}
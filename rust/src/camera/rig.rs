@@ -0,0 +1,76 @@
+use bevy::app::Events;
+use bevy::prelude::Component;
+use gdnative::api::Camera2D;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::builder::GodotEntity;
+use crate::camera::CameraFocus;
+
+/// Camera Rig Component.
+/// Follows the player with smoothing and a deadzone, stays inside the
+/// current bounds and shakes with trauma.
+#[gdrust(extends = Camera2D)]
+#[derive(Component, Clone)]
+pub struct CameraRig {
+    #[default(_owner.claim())]
+    pub owner: Ref<Camera2D>,
+    /// How quickly the camera catches up, per second.
+    #[export]
+    #[default(6.)]
+    pub smoothing: f32,
+    /// Half size of the area the player can move in without the camera following.
+    #[export]
+    #[default(Vector2::new(16., 12.))]
+    pub deadzone: Vector2,
+    /// Largest shake offset, reached at full trauma.
+    #[export]
+    #[default(Vector2::new(6., 4.))]
+    pub max_shake: Vector2,
+    /// Trauma lost per second.
+    #[export]
+    #[default(1.5)]
+    pub trauma_decay: f32,
+    /// Level bounds, unbounded if empty. Rooms override them.
+    #[export]
+    #[default(Rect2::new(Vector2::ZERO, Vector2::ZERO))]
+    pub level_bounds: Rect2,
+    pub room_bounds: Option<Rect2>,
+    pub trauma: f32,
+    pub center: Option<Vector2>,
+}
+
+#[methods]
+impl CameraRig {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Camera2D>) {
+        owner.set_enable_follow_smoothing(false);
+
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+
+    /// Show `point` for `duration` seconds, then go back to the player.
+    #[export]
+    fn focus(&self, _owner: TRef<Camera2D>, point: Vector2, duration: f32) {
+        with_world(|w| {
+            if let Some(mut events) = w.get_resource_mut::<Events<CameraFocus>>() {
+                events.send(CameraFocus { point, duration });
+            }
+        });
+    }
+}
+
+impl CameraRig {
+    /// The bounds the camera is kept inside.
+    pub fn bounds(&self) -> Option<Rect2> {
+        let level = Some(self.level_bounds).filter(|bounds| bounds.size != Vector2::ZERO);
+        self.room_bounds.or(level)
+    }
+
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.);
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::Component;
+use gdnative::api::{Area2D, CollisionShape2D, RectangleShape2D};
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+use gdrust::unsafe_functions::{NodeExt, RefExt};
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::connect_area_signals;
+
+/// Room Component.
+/// Limits the camera to its rectangle while the player is inside.
+#[gdrust(extends = Area2D)]
+#[derive(Component, Clone)]
+pub struct Room {
+    #[default(Rect2::new(Vector2::ZERO, Vector2::ZERO))]
+    pub bounds: Rect2,
+}
+
+#[methods]
+impl Room {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Area2D>) {
+        let shape = owner.expect_node::<CollisionShape2D>("CollisionShape2D");
+        let rect = shape.shape();
+        match rect
+            .as_ref()
+            .and_then(|rect| rect.expect_safe().cast::<RectangleShape2D>())
+        {
+            Some(rect) => {
+                let extents = rect.extents();
+                self.bounds = Rect2::new(shape.global_position() - extents, extents * 2.);
+            }
+            None => godot_error!("{}: room shape must be a rectangle", owner.name()),
+        }
+
+        connect_area_signals(owner);
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+}
@@ -0,0 +1,162 @@
+use rand::Rng;
+
+use crate::components::{DamageKind, Defense};
+use crate::delect_box::hit_box::HitBox;
+
+/// The attacking side of a hit.
+#[derive(Clone, Copy)]
+pub struct Attack {
+    pub damage: i32,
+    pub kind: DamageKind,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+    /// Damage is scaled by a random factor in `1 - variance..=1 + variance`.
+    pub variance: f32,
+}
+
+impl From<&HitBox> for Attack {
+    fn from(hitbox: &HitBox) -> Self {
+        Self {
+            damage: hitbox.damage,
+            kind: hitbox.kind,
+            crit_chance: hitbox.crit_chance,
+            crit_multiplier: hitbox.crit_multiplier,
+            variance: hitbox.variance,
+        }
+    }
+}
+
+/// The damage a hit deals.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub amount: i32,
+    pub crit: bool,
+}
+
+/// Roll the damage of `attack` against `defense`.
+/// Variance and crits apply first, then flat armor, then the resistance to
+/// the attack's damage kind. Damage never goes below zero.
+pub fn resolve(attack: &Attack, defense: Option<&Defense>, rng: &mut impl Rng) -> Hit {
+    let crit = rng.gen::<f32>() < attack.crit_chance;
+
+    let mut damage = attack.damage as f32;
+    if attack.variance > 0. {
+        damage *= 1. + rng.gen_range(-attack.variance..=attack.variance);
+    }
+    if crit {
+        damage *= attack.crit_multiplier;
+    }
+    if let Some(defense) = defense {
+        damage -= defense.armor as f32;
+        damage *= 1. - defense.resistance(attack.kind);
+    }
+
+    Hit {
+        amount: damage.round().max(0.) as i32,
+        crit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn attack(damage: i32) -> Attack {
+        Attack {
+            damage,
+            kind: DamageKind::Slash,
+            crit_chance: 0.,
+            crit_multiplier: 2.,
+            variance: 0.,
+        }
+    }
+
+    fn defense(armor: i32) -> Defense {
+        Defense {
+            armor,
+            ..Defense::default()
+        }
+    }
+
+    #[test]
+    fn flat_damage() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let hit = resolve(&attack(3), None, &mut rng);
+        assert_eq!((hit.amount, hit.crit), (3, false));
+    }
+
+    #[test]
+    fn crit_multiplies() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let attack = Attack {
+            crit_chance: 1.,
+            ..attack(3)
+        };
+        let hit = resolve(&attack, None, &mut rng);
+        assert_eq!((hit.amount, hit.crit), (6, true));
+    }
+
+    #[test]
+    fn armor_then_resistance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let defense = Defense {
+            slash: 0.5,
+            ..defense(2)
+        };
+        assert_eq!(resolve(&attack(10), Some(&defense), &mut rng).amount, 4);
+    }
+
+    #[test]
+    fn resistance_only_to_its_kind() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let defense = Defense {
+            fire: 1.,
+            ..defense(0)
+        };
+        assert_eq!(resolve(&attack(4), Some(&defense), &mut rng).amount, 4);
+
+        let fire = Attack {
+            kind: DamageKind::Fire,
+            ..attack(4)
+        };
+        assert_eq!(resolve(&fire, Some(&defense), &mut rng).amount, 0);
+    }
+
+    #[test]
+    fn never_negative() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(resolve(&attack(1), Some(&defense(5)), &mut rng).amount, 0);
+    }
+
+    #[test]
+    fn variance_stays_in_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let attack = Attack {
+            variance: 0.2,
+            ..attack(10)
+        };
+        for _ in 0..1000 {
+            let amount = resolve(&attack, None, &mut rng).amount;
+            assert!((8..=12).contains(&amount), "{}", amount);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_hits() {
+        let attack = Attack {
+            crit_chance: 0.3,
+            variance: 0.5,
+            ..attack(10)
+        };
+        let roll = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| resolve(&attack, None, &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(roll(42), roll(42));
+    }
+}
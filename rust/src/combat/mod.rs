@@ -0,0 +1,53 @@
+use bevy::prelude::{App, Entity, ParallelSystemDescriptorCoercion, Plugin, Res, ResMut};
+use gdnative::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::components::DamageKind;
+use crate::delect_box::hit_box::hitbox_event_system;
+use crate::tuning::Tuning;
+
+pub mod formula;
+
+/// Sent whenever an entity takes damage.
+pub struct Damaged {
+    pub target: Entity,
+    pub source: Entity,
+    pub amount: i32,
+    pub kind: DamageKind,
+    pub crit: bool,
+    /// Global position of the target when it was hit.
+    pub position: Vector2,
+}
+
+/// Combat Rng Resource.
+/// Rolls crits and variance; seeded from `tuning.ron` for reproducible
+/// fights, from entropy otherwise.
+pub struct CombatRng(pub StdRng);
+
+impl Default for CombatRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+/// Combat Rng System.
+/// Reseeds the rng whenever the tuning sets a seed.
+pub fn combat_rng_system(tuning: Res<Tuning>, mut rng: ResMut<CombatRng>) {
+    if !tuning.is_changed() {
+        return;
+    }
+    if let Some(seed) = tuning.combat.seed {
+        rng.0 = StdRng::seed_from_u64(seed);
+    }
+}
+
+pub struct CombatPlugin;
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatRng>()
+            .add_event::<Damaged>()
+            .add_system(combat_rng_system)
+            .add_system(hitbox_event_system.after("animation_event"));
+    }
+}
@@ -1,63 +1,131 @@
 use bevy::prelude::Component;
 use defaults::Defaults;
+use gdnative::export::hint::{EnumHint, IntHint};
 use gdnative::export::Export;
 use gdnative::prelude::*;
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
+use crate::inspector::Inspect;
+
 /// Stats Component.
-#[derive(Component, Defaults, ToVariant, FromVariant, Copy, Clone)]
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
+#[inspect(validate = validate_stats)]
 pub struct Stats {
     #[def = "4"]
+    #[inspect(min = 1, max = 99)]
     pub max_health: i32,
     #[def = "4"]
+    #[inspect(min = 0, max = 99)]
     pub health: i32,
 }
-impl Export for Stats {
-    type Hint = ();
-    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-        ExportInfo::new(VariantType::Dictionary)
+
+fn validate_stats(stats: &mut Stats, owner: &str) {
+    if stats.health > stats.max_health {
+        godot_warn!(
+            "{}: health {} is above max_health {}, clamped",
+            owner,
+            stats.health,
+            stats.max_health
+        );
+        stats.health = stats.max_health;
     }
 }
 
 /// Roll Component.
-#[derive(Component, Defaults, ToVariant, FromVariant, Copy, Clone)]
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
 pub struct Roll {
+    /// Top speed of the roll.
     #[def = "120.0"]
+    #[inspect(min = 0, max = 1000)]
     pub roll_speed: f32,
-    pub roll_velocity: Vector2,
+    /// Fraction of the roll spent speeding up.
+    #[inspect(min = 0, max = 1)]
+    pub ease_in: f32,
+    /// Fraction of the roll spent slowing down.
+    #[def = "0.3"]
+    #[inspect(min = 0, max = 1)]
+    pub ease_out: f32,
+    /// Fraction of the roll after which an attack cancels it, never if 1.
+    #[def = "0.6"]
+    #[inspect(min = 0, max = 1)]
+    pub cancel_after: f32,
+    /// Seconds after a roll before the next one.
+    #[def = "0.2"]
+    #[inspect(min = 0, max = 10)]
+    pub cooldown: f32,
 }
-impl Export for Roll {
-    type Hint = ();
-    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-        ExportInfo::new(VariantType::Dictionary)
+
+impl Roll {
+    /// Speed at `progress`, from 0 to 1 through the roll.
+    pub fn speed(&self, progress: f32) -> f32 {
+        let smoothstep = |t: f32| {
+            let t = t.clamp(0., 1.);
+            t * t * (3. - 2. * t)
+        };
+
+        let mut factor = 1f32;
+        if self.ease_in > 0. {
+            factor = factor.min(smoothstep(progress / self.ease_in));
+        }
+        if self.ease_out > 0. {
+            factor = factor.min(smoothstep((1. - progress) / self.ease_out));
+        }
+        self.roll_speed * factor
+    }
+
+    pub fn can_cancel(&self, progress: f32) -> bool {
+        self.cancel_after < 1. && progress >= self.cancel_after
     }
 }
 
+/// Roll Cooldown Component.
+/// Seconds left before the next roll.
+#[derive(Component, Default, Copy, Clone)]
+pub struct RollCooldown(pub f32);
+
 /// Accelerates Component.
-#[derive(Component, Defaults, ToVariant, FromVariant, Copy, Clone)]
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
 pub struct Acceleration {
     #[def = "80.0"]
+    #[inspect(min = 0, max = 1000)]
     pub max_speed: f32,
     #[def = "500.0"]
+    #[inspect(min = 0, max = 10000)]
     pub acceleration: f32,
 }
-impl Export for Acceleration {
-    type Hint = ();
-    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-        ExportInfo::new(VariantType::Dictionary)
-    }
-}
 
 /// Friction Component.
-#[derive(Component, Defaults, ToVariant, FromVariant, Copy, Clone)]
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
 pub struct Friction {
     #[def = "400.0"]
+    #[inspect(min = 0, max = 10000)]
     pub friction: f32,
 }
-impl Export for Friction {
-    type Hint = ();
-    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-        ExportInfo::new(VariantType::Dictionary)
+
+/// Defense Component.
+/// Flat armor subtracted from every hit, then a resistance per damage kind;
+/// 1 is immune, negative values are weaknesses.
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
+pub struct Defense {
+    #[inspect(min = 0, max = 99)]
+    pub armor: i32,
+    #[inspect(min = -1, max = 1)]
+    pub slash: f32,
+    #[inspect(min = -1, max = 1)]
+    pub blunt: f32,
+    #[inspect(min = -1, max = 1)]
+    pub fire: f32,
+}
+
+impl Defense {
+    pub fn resistance(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Any => 0.,
+            DamageKind::Slash => self.slash,
+            DamageKind::Blunt => self.blunt,
+            DamageKind::Fire => self.fire,
+        }
     }
 }
 
@@ -82,7 +150,87 @@ impl DerefMut for Velocity {
 }
 
 /// Knockback Component.
-#[derive(Component, Default, Copy, Clone)]
+/// Velocity from hits, added on top of the body's own velocity.
+#[derive(Component, Defaults, ToVariant, FromVariant, Inspect, Copy, Clone)]
 pub struct Knockback {
     pub vector: Vector2,
+    /// Impulses are divided by the mass.
+    #[def = "1.0"]
+    #[inspect(min = 0.1, max = 100)]
+    pub mass: f32,
+    /// Speed lost per second.
+    #[def = "200.0"]
+    #[inspect(min = 0, max = 10000)]
+    pub decay: f32,
+}
+
+impl Knockback {
+    pub fn apply(&mut self, impulse: Vector2) {
+        self.vector += impulse / self.mass;
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.vector = self.vector.move_toward(Vector2::ZERO, self.decay * delta);
+    }
+}
+
+/// Inventory Component.
+/// Item counts keyed by item name.
+#[derive(Component, Default, Clone)]
+pub struct Inventory {
+    pub items: HashMap<String, u32>,
+}
+
+/// Damage Kind.
+/// What a hitbox deals, and what a destructible needs to break.
+#[derive(Default, Copy, Clone, Eq, PartialEq)]
+pub enum DamageKind {
+    #[default]
+    Any,
+    Slash,
+    Blunt,
+    Fire,
+}
+
+const DAMAGE_KINDS: [DamageKind; 4] = [
+    DamageKind::Any,
+    DamageKind::Slash,
+    DamageKind::Blunt,
+    DamageKind::Fire,
+];
+
+impl DamageKind {
+    /// Whether damage of this kind breaks something that requires `required`.
+    pub fn satisfies(self, required: DamageKind) -> bool {
+        required == DamageKind::Any || self == required
+    }
+}
+
+impl ToVariant for DamageKind {
+    fn to_variant(&self) -> Variant {
+        (*self as i64).to_variant()
+    }
+}
+impl FromVariant for DamageKind {
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        let index = i64::from_variant(variant)?;
+        DAMAGE_KINDS
+            .get(index as usize)
+            .copied()
+            .ok_or(FromVariantError::Unspecified)
+    }
+}
+impl Export for DamageKind {
+    type Hint = IntHint<i64>;
+    fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
+        hint.unwrap_or_else(|| {
+            IntHint::Enum(EnumHint::new(
+                ["Any", "Slash", "Blunt", "Fire"]
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            ))
+        })
+        .export_info()
+    }
 }
@@ -1,7 +1,13 @@
-use bevy::prelude::Component;
-use gdnative::api::Area2D;
+use bevy::prelude::{Component, Entity, EventReader, Query};
+use gdnative::api::{Area2D, CollisionShape2D};
 use gdnative::prelude::*;
 use gdrust::macros::*;
+use gdrust::unsafe_functions::RefExt;
+
+use crate::animation::AnimationEvent;
+use crate::bridge::connect_area_signals;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::components::DamageKind;
 
 /// HitBox Component.
 #[gdrust(extends = Area2D)]
@@ -12,7 +18,104 @@ pub struct HitBox {
     #[export]
     #[default(1)]
     pub damage: i32,
+    #[export]
+    pub kind: DamageKind,
+    /// Chance between 0 and 1 that a hit is critical.
+    #[export]
+    pub crit_chance: f32,
+    #[export]
+    #[default(2.)]
+    pub crit_multiplier: f32,
+    /// Damage varies randomly by up to this fraction.
+    #[export]
+    pub variance: f32,
+    /// Direction of the knockback, away from the hitbox if zero.
     pub knockback: Vector2,
 }
 #[methods]
-impl HitBox {}
+impl HitBox {
+    #[export]
+    fn _ready(&self, owner: TRef<Area2D>) {
+        connect_area_signals(owner);
+    }
+}
+
+impl HitBox {
+    /// Direction a target at `position` is knocked back in.
+    pub fn knockback_direction(&self, position: Vector2) -> Vector2 {
+        if self.knockback != Vector2::ZERO {
+            return self.knockback;
+        }
+        self.owner.try_safe().map_or(Vector2::ZERO, |hitbox| {
+            hitbox.global_position().direction_to(position)
+        })
+    }
+
+    /// Enable or disable the collision shapes of the hitbox.
+    pub fn set_active(&self, active: bool) {
+        let owner = match self.owner.try_safe() {
+            Some(owner) => owner,
+            None => return,
+        };
+        for child in owner.get_children().iter() {
+            if let Some(shape) = child.try_to_object::<CollisionShape2D>() {
+                shape.expect_safe().set_deferred("disabled", !active);
+            }
+        }
+    }
+}
+
+/// Swing Phase.
+#[derive(Default, Clone, Copy, Eq, PartialEq)]
+pub enum SwingPhase {
+    #[default]
+    Startup,
+    Active,
+    Recovery,
+}
+
+/// Swing Component.
+/// A single attack of the entity's hitbox. Hits only land while it is
+/// active, and each target is hit at most once.
+#[derive(Component, Default, Clone)]
+pub struct Swing {
+    pub phase: SwingPhase,
+    hit: Vec<Entity>,
+}
+
+impl Swing {
+    /// Whether a hit on `target` lands, remembering it if so.
+    pub fn land(&mut self, target: Entity) -> bool {
+        if self.phase != SwingPhase::Active || self.hit.contains(&target) {
+            return false;
+        }
+        self.hit.push(target);
+        true
+    }
+}
+
+/// HitBox Event System.
+/// Turns hitboxes on and off on "hitbox_on" and "hitbox_off" animation
+/// events, moving swings from startup to active to recovery.
+pub fn hitbox_event_system(
+    mut events: EventReader<AnimationEvent>,
+    mut hitbox: Query<(&HitBox, Option<&mut Swing>)>,
+) {
+    for AnimationEvent { entity, name } in events.iter() {
+        let active = match name.as_str() {
+            "hitbox_on" => true,
+            "hitbox_off" => false,
+            _ => continue,
+        };
+        if let Ok((hitbox, swing)) = hitbox.get_mut(*entity) {
+            hitbox.set_active(active);
+            if let Some(mut swing) = swing {
+                swing.phase = if active {
+                    SwingPhase::Active
+                } else {
+                    SwingPhase::Recovery
+                };
+            }
+        }
+    }
+}
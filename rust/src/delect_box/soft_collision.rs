@@ -1,35 +1,46 @@
-use bevy::prelude::{Component, Query};
+use bevy::prelude::{Component, Entity, Query, Res};
 use gdnative::api::Area2D;
 use gdnative::prelude::*;
 use gdrust::macros::*;
-use gdrust::unsafe_functions::RefExt;
 
-/// HurtBox Component.
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::spatial::SpatialGrid;
+
+/// Soft Collision Component.
+/// Pushes overlapping bodies apart.
 #[gdrust(extends = Area2D)]
 #[derive(Component, Clone)]
 pub struct SoftCollision {
     #[default(_owner.claim())]
     pub owner: Ref<Area2D>,
-    pub input_vector: Vector2,
+    /// Speed of the push away from an overlapping area.
+    #[export]
+    #[default(30.)]
+    pub push_speed: f32,
+    pub push: Vector2,
 }
 #[methods]
 impl SoftCollision {}
 
-pub fn soft_collision_system(mut soft_collision: Query<&mut SoftCollision>) {
-    for mut soft_collision in soft_collision.iter_mut() {
-        let soft_collision_area = soft_collision.owner.expect_safe();
-        let areas = soft_collision_area.get_overlapping_areas();
+/// Soft Collision System.
+/// Pushes each soft collision away from the first nearby one it overlaps.
+pub fn soft_collision_system(
+    grid: Res<SpatialGrid>,
+    mut soft_collision: Query<(Entity, &mut SoftCollision)>,
+) {
+    for (entity, mut soft_collision) in soft_collision.iter_mut() {
+        let soft_collision_area = match soft_collision.owner.try_safe() {
+            Some(soft_collision_area) => soft_collision_area,
+            None => continue,
+        };
 
-        if !areas.is_empty() {
-            let area = areas
-                .get(0)
-                .try_to_object::<Area2D>()
-                .unwrap()
-                .expect_safe();
-            soft_collision.input_vector =
-                area.global_position() - soft_collision_area.global_position();
-        } else {
-            soft_collision.input_vector = Vector2::ZERO;
-        }
+        soft_collision.push = match grid.overlapping(entity, soft_collision_area).next() {
+            Some((_, area)) => {
+                area.global_position()
+                    .direction_to(soft_collision_area.global_position())
+                    * soft_collision.push_speed
+            }
+            None => Vector2::ZERO,
+        };
     }
 }
@@ -0,0 +1,539 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::{
+    App, Entity, EventWriter, ParallelSystemDescriptorCoercion, Plugin, Query, ResMut, With,
+};
+use gdnative::api::File;
+use gdnative::prelude::*;
+use serde::Deserialize;
+
+use crate::components::Inventory;
+use crate::dialogue::npc::{interaction_area_system, InteractionArea, Npc};
+use crate::dialogue::ui::dialogue_ui_system;
+use crate::player::{Player, PlayerFsm, PlayerState};
+use crate::save::{Autosave, Save};
+
+pub mod npc;
+pub mod ui;
+
+/// Must hold for an entry, node or choice to be used.
+#[derive(Deserialize, Clone, Debug)]
+pub enum Condition {
+    /// The player carries at least this many of an item.
+    HasItem(String, u32),
+    Flag(String),
+    NotFlag(String),
+}
+
+/// Run when a node is shown or a choice is picked.
+#[derive(Deserialize, Clone, Debug)]
+pub enum Action {
+    SetFlag(String),
+    ClearFlag(String),
+    GiveItem(String, u32),
+    /// Takes up to this many of an item.
+    TakeItem(String, u32),
+}
+
+/// What conditions read and actions change.
+pub struct Context<'a> {
+    pub flags: &'a mut HashSet<String>,
+    pub inventory: &'a mut Inventory,
+}
+
+impl Condition {
+    pub fn holds(&self, ctx: &Context) -> bool {
+        match self {
+            Condition::HasItem(item, count) => {
+                ctx.inventory.items.get(item).copied().unwrap_or(0) >= *count
+            }
+            Condition::Flag(flag) => ctx.flags.contains(flag),
+            Condition::NotFlag(flag) => !ctx.flags.contains(flag),
+        }
+    }
+}
+
+impl Action {
+    pub fn apply(&self, ctx: &mut Context) {
+        match self {
+            Action::SetFlag(flag) => {
+                ctx.flags.insert(flag.clone());
+            }
+            Action::ClearFlag(flag) => {
+                ctx.flags.remove(flag);
+            }
+            Action::GiveItem(item, count) => {
+                *ctx.inventory.items.entry(item.clone()).or_insert(0) += count;
+            }
+            Action::TakeItem(item, count) => {
+                if let Some(held) = ctx.inventory.items.get_mut(item) {
+                    *held = held.saturating_sub(*count);
+                    if *held == 0 {
+                        ctx.inventory.items.remove(item);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn all_hold(conditions: &[Condition], ctx: &Context) -> bool {
+    conditions.iter().all(|condition| condition.holds(ctx))
+}
+
+/// A node to go to if its conditions hold.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Branch {
+    pub node: String,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+}
+
+/// An answer the player can give.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Choice {
+    pub text: String,
+    /// Node shown next, the dialogue ends if unset.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// The choice is hidden unless these hold.
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// A line of dialogue.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct DialogueNode {
+    /// Who says the line, the NPC if unset.
+    pub speaker: Option<String>,
+    pub text: String,
+    pub actions: Vec<Action>,
+    pub choices: Vec<Choice>,
+    /// Node shown next when there are no choices, the dialogue ends if unset.
+    pub next: Option<String>,
+}
+
+/// A dialogue tree, loaded from a RON file.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Dialogue {
+    /// The first entry whose conditions hold starts the dialogue.
+    pub start: Vec<Branch>,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+impl Dialogue {
+    fn load(path: &str) -> Option<Self> {
+        let file = File::new();
+        if let Err(err) = file.open(path, File::READ) {
+            godot_error!("Could not open {}: {:?}", path, err);
+            return None;
+        }
+        let text = file.get_as_text().to_string();
+        file.close();
+
+        let dialogue: Dialogue = match ron::from_str(&text) {
+            Ok(dialogue) => dialogue,
+            Err(err) => {
+                godot_error!("Could not parse {}: {}", path, err);
+                return None;
+            }
+        };
+        for missing in dialogue.missing_nodes() {
+            godot_warn!("{}: no node `{}`", path, missing);
+        }
+        Some(dialogue)
+    }
+
+    /// Nodes referred to but not defined.
+    pub fn missing_nodes(&self) -> Vec<&str> {
+        let starts = self.start.iter().map(|branch| branch.node.as_str());
+        let nexts = self.nodes.values().flat_map(|node| {
+            node.next
+                .iter()
+                .chain(
+                    node.choices
+                        .iter()
+                        .filter_map(|choice| choice.next.as_ref()),
+                )
+                .map(String::as_str)
+        });
+
+        let mut missing: Vec<&str> = starts
+            .chain(nexts)
+            .filter(|name| !self.nodes.contains_key(*name))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        missing
+    }
+}
+
+/// A dialogue in progress.
+pub struct Conversation {
+    dialogue: Dialogue,
+    /// Who speaks lines without a speaker.
+    speaker: String,
+    node: String,
+    /// Indices of the choices of the current node whose conditions hold.
+    choices: Vec<usize>,
+    selected: usize,
+}
+
+impl Conversation {
+    /// Start `dialogue` at its first entry whose conditions hold.
+    pub fn start(dialogue: Dialogue, speaker: String, ctx: &mut Context) -> Option<Self> {
+        let node = dialogue
+            .start
+            .iter()
+            .find(|branch| all_hold(&branch.conditions, ctx))?
+            .node
+            .clone();
+
+        let mut conversation = Self {
+            dialogue,
+            speaker,
+            node: String::new(),
+            choices: Vec::new(),
+            selected: 0,
+        };
+        if conversation.enter(node, ctx) {
+            Some(conversation)
+        } else {
+            None
+        }
+    }
+
+    /// Show `node`, returning false if it does not exist.
+    fn enter(&mut self, node: String, ctx: &mut Context) -> bool {
+        let current = match self.dialogue.nodes.get(&node) {
+            Some(current) => current,
+            None => return false,
+        };
+        for action in current.actions.iter() {
+            action.apply(ctx);
+        }
+        self.choices = (0..current.choices.len())
+            .filter(|i| all_hold(&current.choices[*i].conditions, ctx))
+            .collect();
+        self.selected = 0;
+        self.node = node;
+        true
+    }
+
+    fn current(&self) -> &DialogueNode {
+        &self.dialogue.nodes[&self.node]
+    }
+
+    /// Move the selection by `step` choices, wrapping around.
+    pub fn select(&mut self, step: isize) {
+        let count = self.choices.len() as isize;
+        if count > 0 {
+            self.selected = (self.selected as isize + step).rem_euclid(count) as usize;
+        }
+    }
+
+    /// Pick the selected choice or continue, returning false once the
+    /// dialogue is over.
+    pub fn confirm(&mut self, ctx: &mut Context) -> bool {
+        let next = match self.choices.get(self.selected) {
+            Some(i) => {
+                let choice = &self.current().choices[*i];
+                for action in choice.actions.iter() {
+                    action.apply(ctx);
+                }
+                choice.next.clone()
+            }
+            None => self.current().next.clone(),
+        };
+
+        match next {
+            Some(next) => self.enter(next, ctx),
+            None => false,
+        }
+    }
+
+    pub fn view(&self) -> DialogueShown {
+        let current = self.current();
+        DialogueShown {
+            speaker: current
+                .speaker
+                .clone()
+                .unwrap_or_else(|| self.speaker.clone()),
+            text: current.text.clone(),
+            choices: self
+                .choices
+                .iter()
+                .map(|i| current.choices[*i].text.clone())
+                .collect(),
+            selected: self.selected,
+        }
+    }
+}
+
+/// Sent whenever a line is shown or the selection changes.
+pub struct DialogueShown {
+    pub speaker: String,
+    pub text: String,
+    pub choices: Vec<String>,
+    pub selected: usize,
+}
+
+/// Sent when a dialogue ends.
+pub struct DialogueEnded;
+
+/// Dialogue Library Resource.
+/// Dialogue files by path, loaded the first time an NPC uses them.
+#[derive(Default)]
+pub struct DialogueLibrary(HashMap<String, Option<Dialogue>>);
+
+impl DialogueLibrary {
+    pub fn get(&mut self, path: &str) -> Option<&Dialogue> {
+        self.0
+            .entry(path.to_string())
+            .or_insert_with(|| Dialogue::load(path))
+            .as_ref()
+    }
+}
+
+/// Active Dialogue Resource.
+/// The conversation in progress and the player taking part.
+#[derive(Default)]
+pub struct ActiveDialogue(Option<(Entity, Conversation)>);
+
+/// Run `f` with the dialogue context of the save and `inventory`, writing the
+/// save if the flags changed.
+fn with_context<T>(
+    save: &mut Save,
+    inventory: &mut Inventory,
+    autosave: &mut EventWriter<Autosave>,
+    f: impl FnOnce(&mut Context) -> T,
+) -> T {
+    let flags = save.data.flags.clone();
+    let result = f(&mut Context {
+        flags: &mut save.data.flags,
+        inventory,
+    });
+    if save.data.flags != flags {
+        autosave.send(Autosave);
+    }
+    result
+}
+
+/// Dialogue System.
+/// Starts dialogues with NPCs in reach on "interact", then walks through
+/// them: up and down pick a choice, "interact" confirms, "ui_cancel" leaves.
+pub fn dialogue_system(
+    mut active: ResMut<ActiveDialogue>,
+    mut library: ResMut<DialogueLibrary>,
+    mut save: ResMut<Save>,
+    mut autosave: EventWriter<Autosave>,
+    mut shown: EventWriter<DialogueShown>,
+    mut ended: EventWriter<DialogueEnded>,
+    npc: Query<(&Npc, &InteractionArea)>,
+    mut player: Query<(&mut PlayerFsm, &PlayerState, &mut Inventory), With<Player>>,
+) {
+    let input = Input::godot_singleton();
+
+    if let Some((entity, conversation)) = active.0.as_mut() {
+        let over = match player.get_mut(*entity) {
+            // hits and death cut the dialogue short
+            Ok((_, PlayerState::HURT | PlayerState::DEAD, _)) | Err(_) => true,
+            Ok((mut fsm, state, mut inventory)) => {
+                let mut over = input.is_action_just_pressed("ui_cancel", false);
+                if input.is_action_just_pressed("ui_up", false) {
+                    conversation.select(-1);
+                    shown.send(conversation.view());
+                }
+                if input.is_action_just_pressed("ui_down", false) {
+                    conversation.select(1);
+                    shown.send(conversation.view());
+                }
+                if !over && input.is_action_just_pressed("interact", false) {
+                    let continues = with_context(&mut save, &mut inventory, &mut autosave, |ctx| {
+                        conversation.confirm(ctx)
+                    });
+                    if continues {
+                        shown.send(conversation.view());
+                    } else {
+                        over = true;
+                    }
+                }
+                if over {
+                    fsm.request(*state, PlayerState::MOVE);
+                }
+                over
+            }
+        };
+        if over {
+            active.0 = None;
+            ended.send(DialogueEnded);
+        }
+        return;
+    }
+
+    if !input.is_action_just_pressed("interact", false) {
+        return;
+    }
+    for (npc, area) in npc.iter() {
+        let entity = match area.player {
+            Some(entity) => entity,
+            None => continue,
+        };
+        let (mut fsm, state, mut inventory) = match player.get_mut(entity) {
+            Ok(player) => player,
+            Err(_) => continue,
+        };
+        if *state != PlayerState::MOVE {
+            continue;
+        }
+        let dialogue = match library.get(&npc.dialogue) {
+            Some(dialogue) => dialogue.clone(),
+            None => continue,
+        };
+
+        let speaker = npc.speaker.clone();
+        let conversation = with_context(&mut save, &mut inventory, &mut autosave, |ctx| {
+            Conversation::start(dialogue, speaker, ctx)
+        });
+        if let Some(conversation) = conversation {
+            fsm.request(*state, PlayerState::INTERACT);
+            shown.send(conversation.view());
+            active.0 = Some((entity, conversation));
+        }
+        return;
+    }
+}
+
+pub struct DialoguePlugin;
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DialogueLibrary>()
+            .init_resource::<ActiveDialogue>()
+            .add_event::<DialogueShown>()
+            .add_event::<DialogueEnded>()
+            .add_system(
+                interaction_area_system
+                    .label("interaction_area")
+                    .after("node_signal"),
+            )
+            .add_system(
+                dialogue_system
+                    .label("dialogue")
+                    .after("interaction_area")
+                    .after("save_load")
+                    .before("player_fsm"),
+            )
+            .add_system(dialogue_ui_system.after("dialogue"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIALOGUE: &str = r#"(
+        start: [
+            (node: "again", conditions: [Flag("met")]),
+            (node: "hello"),
+        ],
+        nodes: {
+            "hello": (
+                text: "Hello.",
+                actions: [SetFlag("met")],
+                choices: [
+                    (text: "Trade", next: Some("trade"), conditions: [HasItem("seed", 2)]),
+                    (text: "Bye"),
+                ],
+            ),
+            "trade": (
+                text: "Thanks.",
+                actions: [TakeItem("seed", 2), GiveItem("heart", 1)],
+            ),
+            "again": (text: "Hello again.", next: Some("gone")),
+        },
+    )"#;
+
+    fn dialogue() -> Dialogue {
+        ron::from_str(DIALOGUE).unwrap()
+    }
+
+    fn inventory(seeds: u32) -> Inventory {
+        let mut inventory = Inventory::default();
+        inventory.items.insert("seed".to_string(), seeds);
+        inventory
+    }
+
+    #[test]
+    fn finds_missing_nodes() {
+        assert_eq!(dialogue().missing_nodes(), vec!["gone"]);
+    }
+
+    #[test]
+    fn starts_at_first_entry_that_holds() {
+        let mut flags = HashSet::new();
+        let mut inventory = inventory(0);
+        let mut ctx = Context {
+            flags: &mut flags,
+            inventory: &mut inventory,
+        };
+
+        let first = Conversation::start(dialogue(), "Npc".to_string(), &mut ctx).unwrap();
+        assert_eq!(first.view().text, "Hello.");
+        assert!(ctx.flags.contains("met"));
+
+        let second = Conversation::start(dialogue(), "Npc".to_string(), &mut ctx).unwrap();
+        assert_eq!(second.view().text, "Hello again.");
+    }
+
+    #[test]
+    fn hides_choices_that_do_not_hold() {
+        let mut flags = HashSet::new();
+        let mut inventory = inventory(1);
+        let mut ctx = Context {
+            flags: &mut flags,
+            inventory: &mut inventory,
+        };
+
+        let conversation = Conversation::start(dialogue(), "Npc".to_string(), &mut ctx).unwrap();
+        assert_eq!(conversation.view().choices, vec!["Bye"]);
+    }
+
+    #[test]
+    fn choices_lead_on_and_run_actions() {
+        let mut flags = HashSet::new();
+        let mut inventory = inventory(2);
+        let mut ctx = Context {
+            flags: &mut flags,
+            inventory: &mut inventory,
+        };
+
+        let mut conversation =
+            Conversation::start(dialogue(), "Npc".to_string(), &mut ctx).unwrap();
+        assert!(conversation.confirm(&mut ctx));
+        assert_eq!(conversation.view().text, "Thanks.");
+        assert_eq!(ctx.inventory.items.get("seed"), None);
+        assert_eq!(ctx.inventory.items.get("heart"), Some(&1));
+        assert!(!conversation.confirm(&mut ctx));
+    }
+
+    #[test]
+    fn selection_wraps() {
+        let mut flags = HashSet::new();
+        let mut inventory = inventory(2);
+        let mut ctx = Context {
+            flags: &mut flags,
+            inventory: &mut inventory,
+        };
+
+        let mut conversation =
+            Conversation::start(dialogue(), "Npc".to_string(), &mut ctx).unwrap();
+        conversation.select(-1);
+        assert_eq!(conversation.view().selected, 1);
+        conversation.select(1);
+        assert_eq!(conversation.view().selected, 0);
+    }
+}
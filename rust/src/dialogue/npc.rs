@@ -0,0 +1,69 @@
+use bevy::prelude::{Component, Entity, EventReader, Query, With};
+use gdnative::api::{Area2D, StaticBody2D};
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::{connect_area_signals, BodyEntered, BodyExited};
+use crate::player::Player;
+
+/// Npc.
+/// Talks to the player through the dialogue in `dialogue` when they press
+/// "interact" inside its `InteractionArea`.
+#[gdrust(extends = StaticBody2D)]
+#[derive(Component, Clone)]
+pub struct Npc {
+    /// Name shown over its lines.
+    #[export]
+    pub speaker: String,
+    /// Path of the dialogue file, e.g. `res://dialogue/old_man.ron`.
+    #[export]
+    pub dialogue: String,
+}
+
+#[methods]
+impl Npc {
+    #[export]
+    fn _ready(&mut self, owner: TRef<StaticBody2D>) {
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone()).node(
+                "InteractionArea",
+                |area: TRef<Area2D>| {
+                    connect_area_signals(area);
+                    InteractionArea { player: None }
+                },
+            );
+        });
+    }
+}
+
+/// Interaction Area Component.
+/// The player within reach of an NPC, if any.
+#[derive(Component, Clone, Copy)]
+pub struct InteractionArea {
+    pub player: Option<Entity>,
+}
+
+/// Interaction Area System.
+/// Tracks the player entering and leaving interaction areas.
+pub fn interaction_area_system(
+    mut body_entered: EventReader<BodyEntered>,
+    mut body_exited: EventReader<BodyExited>,
+    mut area: Query<&mut InteractionArea>,
+    player: Query<(), With<Player>>,
+) {
+    for BodyEntered { a, b } in body_entered.iter() {
+        if let (Ok(mut area), true) = (area.get_mut(*a), player.contains(*b)) {
+            area.player = Some(*b);
+        }
+    }
+
+    for BodyExited { a, b } in body_exited.iter() {
+        if let Ok(mut area) = area.get_mut(*a) {
+            if area.player == Some(*b) {
+                area.player = None;
+            }
+        }
+    }
+}
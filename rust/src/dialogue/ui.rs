@@ -0,0 +1,92 @@
+use bevy::prelude::{Component, EventReader, Query};
+use gdnative::api::Label;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+use gdrust::unsafe_functions::NodeExt;
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::dialogue::{DialogueEnded, DialogueShown};
+
+/// Dialogue Box.
+/// Shows the current line and choices, hidden outside dialogues.
+#[gdrust(extends = Control)]
+#[derive(Component, Clone)]
+pub struct DialogueBox {
+    #[default(_owner.claim())]
+    pub owner: Ref<Control>,
+    pub speaker: Option<Ref<Label>>,
+    pub text: Option<Ref<Label>>,
+    pub choices: Option<Ref<Label>>,
+}
+
+#[methods]
+impl DialogueBox {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Control>) {
+        self.speaker = Some(owner.expect_node::<Label>("Speaker").claim());
+        self.text = Some(owner.expect_node::<Label>("Text").claim());
+        self.choices = Some(owner.expect_node::<Label>("Choices").claim());
+        owner.hide();
+
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+}
+
+fn set_text(label: Option<Ref<Label>>, text: &str) {
+    if let Some(label) = label.and_then(|label| label.try_safe()) {
+        label.set_text(text);
+    }
+}
+
+/// Dialogue Ui System.
+/// Shows the latest line on every dialogue box, and hides them when the
+/// dialogue ends.
+pub fn dialogue_ui_system(
+    mut shown: EventReader<DialogueShown>,
+    mut ended: EventReader<DialogueEnded>,
+    dialogue_box: Query<&DialogueBox>,
+) {
+    let shown = shown.iter().last();
+    let ended = ended.iter().count() > 0;
+    if shown.is_none() && !ended {
+        return;
+    }
+
+    for dialogue_box in dialogue_box.iter() {
+        let owner = match dialogue_box.owner.try_safe() {
+            Some(owner) => owner,
+            None => continue,
+        };
+
+        if let Some(DialogueShown {
+            speaker,
+            text,
+            choices,
+            selected,
+        }) = shown
+        {
+            let choices = choices
+                .iter()
+                .enumerate()
+                .map(|(i, choice)| {
+                    let cursor = if i == *selected { ">" } else { " " };
+                    format!("{} {}", cursor, choice)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            set_text(dialogue_box.speaker, speaker);
+            set_text(dialogue_box.text, text);
+            set_text(dialogue_box.choices, &choices);
+            owner.show();
+        }
+
+        if ended {
+            owner.hide();
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::prelude::{Commands, Component, Entity, EventReader, Query, Res, ResMut, Time, Timer};
+use gdnative::api::Label;
+use gdnative::prelude::*;
+
+use crate::bridge::lifecycle::{scene_tree, SafeRefExt};
+use crate::combat::Damaged;
+use crate::components::DamageKind;
+
+/// Seconds a number stays on screen.
+const LIFETIME: f32 = 0.6;
+/// Pixels per second a number rises.
+const RISE_SPEED: f32 = 24.;
+/// Labels are drawn with the default font, which is large for the viewport.
+const SCALE: f32 = 0.5;
+const CRIT_SCALE: f32 = 0.75;
+
+/// Floating Text Component.
+#[derive(Component)]
+pub struct FloatingText {
+    label: Ref<Label>,
+    position: Vector2,
+    timer: Timer,
+}
+
+/// Floating Text Pool Resource.
+/// Labels live under a layer at the root, so they survive scene changes and
+/// are reused instead of freed.
+#[derive(Default)]
+pub struct FloatingTextPool {
+    layer: Option<Ref<Node2D>>,
+    free: Vec<Ref<Label>>,
+}
+
+impl FloatingTextPool {
+    fn layer<'a>(&mut self) -> Option<TRef<'a, Node2D>> {
+        if let Some(layer) = self.layer.and_then(|layer| layer.try_safe()) {
+            return Some(layer);
+        }
+
+        // the labels went with the old layer
+        self.free.clear();
+        let root = scene_tree()?.root().and_then(|root| root.try_safe())?;
+        let layer = Node2D::new().into_shared();
+        let layer = unsafe { layer.assume_safe() };
+        layer.set_z_index(100);
+        root.add_child(layer, false);
+        self.layer = Some(layer.claim());
+        Some(layer)
+    }
+
+    fn take<'a>(&mut self) -> Option<TRef<'a, Label>> {
+        let layer = self.layer()?;
+        if let Some(label) = self.free.pop().and_then(|label| label.try_safe()) {
+            return Some(label);
+        }
+
+        let label = Label::new().into_shared();
+        let label = unsafe { label.assume_safe() };
+        layer.add_child(label, false);
+        Some(label)
+    }
+}
+
+fn kind_color(kind: DamageKind) -> Color {
+    match kind {
+        DamageKind::Any | DamageKind::Slash => Color::from_rgb(1., 1., 1.),
+        DamageKind::Blunt => Color::from_rgb(0.8, 0.8, 1.),
+        DamageKind::Fire => Color::from_rgb(1., 0.55, 0.2),
+    }
+}
+
+/// Floating Text Spawn System.
+/// Shows a damage number at every hit.
+pub fn floating_text_spawn_system(
+    mut commands: Commands,
+    mut damaged: EventReader<Damaged>,
+    mut pool: ResMut<FloatingTextPool>,
+) {
+    for Damaged {
+        amount,
+        kind,
+        crit,
+        position,
+        ..
+    } in damaged.iter()
+    {
+        let label = match pool.take() {
+            Some(label) => label,
+            None => return,
+        };
+
+        let (text, color, scale) = if *crit {
+            (
+                format!("{}!", amount),
+                Color::from_rgb(1., 0.9, 0.2),
+                CRIT_SCALE,
+            )
+        } else {
+            (amount.to_string(), kind_color(*kind), SCALE)
+        };
+        label.set_text(text);
+        label.set_modulate(color);
+        label.set_scale(Vector2::new(scale, scale));
+        label.show();
+
+        commands.spawn().insert(FloatingText {
+            label: label.claim(),
+            position: *position - label.get_combined_minimum_size() * scale / 2.,
+            timer: Timer::from_seconds(LIFETIME, false),
+        });
+    }
+}
+
+/// Floating Text System.
+/// Rises and fades numbers, then hands their labels back to the pool.
+pub fn floating_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pool: ResMut<FloatingTextPool>,
+    mut text: Query<(Entity, &mut FloatingText)>,
+) {
+    for (entity, mut text) in text.iter_mut() {
+        text.timer.tick(time.delta());
+        let label = match text.label.try_safe() {
+            Some(label) => label,
+            None => {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+
+        if text.timer.finished() {
+            label.hide();
+            pool.free.push(text.label);
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let elapsed = text.timer.elapsed_secs();
+        label.set_position(
+            text.position - Vector2::new(0., RISE_SPEED * elapsed),
+            false,
+        );
+
+        let mut color = label.modulate();
+        color.a = text.timer.percent_left();
+        label.set_modulate(color);
+    }
+}
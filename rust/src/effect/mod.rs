@@ -6,6 +6,14 @@ use gdrust::ecs::engine_sync::components::GodotObjRef;
 use gdrust::macros::gdrust;
 use gdrust::unsafe_functions::{PackedSceneExt, RefExt, ResourceLoaderExt};
 
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::effect::floating_text::{
+    floating_text_spawn_system, floating_text_system, FloatingTextPool,
+};
+use crate::time_scale::{LocalTimeScale, TimeScale};
+
+pub mod floating_text;
+
 #[derive(Component, Default, Clone)]
 pub struct EffectTimer(pub Timer);
 
@@ -15,15 +23,15 @@ pub struct BatDeadEffect {
     pub effect: Ref<PackedScene>,
 }
 
-#[derive(Defaults, Clone)]
-pub struct HitEffect {
-    #[def = "ResourceLoader::godot_singleton().expect_load_scene(\"res://scenes/effect/HitEffect.tscn\")"]
+#[derive(Component, Defaults, Clone)]
+pub struct PlayerDeadEffect {
+    #[def = "ResourceLoader::godot_singleton().expect_load_scene(\"res://scenes/effect/EnemyDeathEffect.tscn\")"]
     pub effect: Ref<PackedScene>,
 }
 
 #[derive(Defaults, Clone)]
-pub struct GrassEffect {
-    #[def = "ResourceLoader::godot_singleton().expect_load_scene(\"res://scenes/effect/GrassEffect.tscn\")"]
+pub struct HitEffect {
+    #[def = "ResourceLoader::godot_singleton().expect_load_scene(\"res://scenes/effect/HitEffect.tscn\")"]
     pub effect: Ref<PackedScene>,
 }
 
@@ -66,12 +74,22 @@ pub fn add_effect(
 pub fn effect_finished(
     mut commands: Commands,
     time: Res<Time>,
-    mut effect: Query<(Entity, &GodotObjRef<AnimatedSprite>, &mut EffectTimer)>,
+    time_scale: Res<TimeScale>,
+    mut effect: Query<(
+        Entity,
+        &GodotObjRef<AnimatedSprite>,
+        &mut EffectTimer,
+        Option<&LocalTimeScale>,
+    )>,
 ) {
-    for (entity, effect, mut timer) in effect.iter_mut() {
-        timer.0.tick(time.delta());
+    for (entity, effect, mut timer, local) in effect.iter_mut() {
+        timer
+            .0
+            .tick(time_scale.duration(time.delta(), entity, local));
         if timer.0.finished() {
-            effect.expect_safe().queue_free();
+            if let Some(effect) = effect.try_safe() {
+                effect.queue_free();
+            }
             commands.entity(entity).despawn();
         }
     }
@@ -81,6 +99,9 @@ pub struct EffectPlugin;
 
 impl Plugin for EffectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(effect_finished);
+        app.init_resource::<FloatingTextPool>()
+            .add_system(effect_finished)
+            .add_system(floating_text_spawn_system)
+            .add_system(floating_text_system);
     }
 }
@@ -1,23 +1,33 @@
-use std::f64::consts::FRAC_PI_4;
-
-use bevy::prelude::{Bundle, Commands, Component, Entity, Query, Res, Timer, With, Without};
-use gdnative::api::{AnimatedSprite, Area2D, KinematicBody2D};
+use bevy::prelude::{
+    Bundle, Commands, Component, Entity, EventReader, EventWriter, Query, Res, ResMut, Timer, With,
+};
+use gdnative::api::{Area2D, KinematicBody2D};
 use gdnative::prelude::*;
 use gdrust::ecs::app::with_world;
-use gdrust::ecs::engine_sync::components::{GodotObjRef, PlayingGame};
 use gdrust::ecs::engine_sync::resources::PhysicsDelta;
 use gdrust::macros::*;
-use gdrust::unsafe_functions::{NodeExt, NodeTreeExt, RefExt};
+use gdrust::unsafe_functions::NodeTreeExt;
 use rand::prelude::SliceRandom;
 
-use crate::components::{Acceleration, Friction, Knockback, Stats, Velocity};
-use crate::delect_box::hit_box::HitBox;
+use crate::animation::{AnimationState, Animator};
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::{connect_area_signals, AreaEntered};
+use crate::combat::{formula, CombatRng, Damaged};
+use crate::components::{Acceleration, Defense, Friction, Knockback, Stats, Velocity};
+use crate::delect_box::hit_box::{HitBox, Swing};
 use crate::delect_box::hurt_box::HurtBox;
 use crate::delect_box::soft_collision::SoftCollision;
 use crate::effect::{add_effect, BatDeadEffect};
 use crate::enemy::wander_controller::WanderTimer;
 use crate::enemy::DelectionZone;
-use crate::player::{Player, PlayerAttacking};
+use crate::inspector::{default_property, get_property, property_list, set_property, Inspect};
+use crate::movement::facing::Facing;
+use crate::movement::{Impulse, KinematicBody};
+use crate::player::Player;
+use crate::time_scale::{LocalTimeScale, TimeScale};
+use crate::tuning::{Tuning, TuningPreset};
+use crate::world::health_bar::HealthBar;
 use crate::WanderController;
 
 #[derive(Component, Clone, Hash, Eq, PartialEq, Default, Copy)]
@@ -28,29 +38,33 @@ pub enum BatState {
     CHASE,
 }
 
+impl AnimationState for BatState {
+    fn animation(&self, _velocity: Vector2) -> &'static str {
+        "fly"
+    }
+}
+
 #[derive(Component, Clone)]
 pub struct Bat {
     pub owner: Ref<KinematicBody2D>,
     pub dead_effect: BatDeadEffect,
 }
 
-#[derive(Component, Default, Clone)]
-pub struct BatKnockback(pub Knockback);
-
 #[gdrust(extends = KinematicBody2D)]
 #[derive(Bundle, Clone)]
 pub struct BatBundle {
     #[default(Bat{ owner: _owner.claim(), dead_effect: BatDeadEffect::default() })]
     pub bat: Bat,
     pub state: BatState,
-    #[export]
     pub stats: Stats,
-    pub knock: BatKnockback,
+    pub defense: Defense,
+    pub knockback: Knockback,
+    pub facing: Facing,
     pub velocity: Velocity,
-    #[export]
     pub acceleration: Acceleration,
-    #[export]
     pub friction: Friction,
+    #[export]
+    pub preset: TuningPreset,
 }
 
 #[methods]
@@ -58,100 +72,139 @@ impl BatBundle {
     #[export]
     fn _ready(&mut self, owner: TRef<KinematicBody2D>) {
         with_world(|w| {
-            w.spawn()
+            GodotEntity::spawn(w, owner)
                 .insert_bundle(self.clone())
-                .insert(
-                    owner
-                        .expect_instance::<HitBox>("Hitbox")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(
-                    owner
-                        .expect_instance::<HurtBox>("Hurtbox")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(
-                    owner
-                        .expect_instance::<SoftCollision>("SoftCollision")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(
-                    owner
-                        .expect_instance::<WanderController>("WanderController")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(WanderTimer(Timer::from_seconds(2., false)))
-                .insert(GodotObjRef::new(
-                    owner.expect_node::<AnimatedSprite>("Sprite").claim(),
-                ))
-                .insert(DelectionZone {
-                    owner: owner.expect_node::<Area2D>("Zone").claim(),
-                    player: None,
+                .insert(KinematicBody::new(owner))
+                .child::<HitBox>("Hitbox")
+                .child::<HurtBox>("Hurtbox")
+                .child::<SoftCollision>("SoftCollision")
+                .child::<WanderController>("WanderController")
+                .child::<HealthBar>("HealthBar")
+                .node("Sprite", Animator::sprite)
+                .node("Zone", |zone: TRef<Area2D>| {
+                    connect_area_signals(zone);
+                    DelectionZone {
+                        owner: zone.claim(),
+                        player: None,
+                    }
                 })
-                .insert(PlayingGame);
+                .insert(WanderTimer(Timer::from_seconds(2., false)));
         });
     }
+
+    fn groups(&self) -> [(&'static str, &dyn Inspect); 5] {
+        [
+            ("stats", &self.stats),
+            ("defense", &self.defense),
+            ("knockback", &self.knockback),
+            ("acceleration", &self.acceleration),
+            ("friction", &self.friction),
+        ]
+    }
+
+    fn groups_mut(&mut self) -> [(&'static str, &mut dyn Inspect); 5] {
+        [
+            ("stats", &mut self.stats),
+            ("defense", &mut self.defense),
+            ("knockback", &mut self.knockback),
+            ("acceleration", &mut self.acceleration),
+            ("friction", &mut self.friction),
+        ]
+    }
+
+    #[export]
+    fn _get_property_list(&self, _owner: TRef<KinematicBody2D>) -> VariantArray {
+        property_list(&self.groups())
+    }
+
+    #[export]
+    fn _get(&self, _owner: TRef<KinematicBody2D>, property: String) -> Variant {
+        get_property(&self.groups(), &property)
+    }
+
+    #[export]
+    fn _set(&mut self, owner: TRef<KinematicBody2D>, property: String, value: Variant) -> bool {
+        let owner = owner.name().to_string();
+        set_property(&mut self.groups_mut(), &property, &value, &owner)
+    }
+
+    #[export]
+    fn property_can_revert(&self, _owner: TRef<KinematicBody2D>, property: String) -> bool {
+        default_property(&self.groups(), &property).is_some()
+    }
+
+    #[export]
+    fn property_get_revert(&self, _owner: TRef<KinematicBody2D>, property: String) -> Variant {
+        default_property(&self.groups(), &property).unwrap_or_else(Variant::nil)
+    }
 }
 
 pub fn bat_system(
     delta: Res<PhysicsDelta>,
+    time_scale: Res<TimeScale>,
+    player: Query<&Player>,
     mut bat: Query<(
+        Entity,
         &mut Velocity,
         &Friction,
         &DelectionZone,
         &mut BatState,
         &Bat,
         &Acceleration,
-        &GodotObjRef<AnimatedSprite>,
+        &mut Facing,
         &mut WanderTimer,
         &WanderController,
+        Option<&LocalTimeScale>,
     )>,
 ) {
     for (
+        entity,
         mut velocity,
         friction,
         delect_zone,
         mut state,
         bat,
         acceleration,
-        sprite,
+        mut facing,
         mut timer,
         wander_controller,
+        local,
     ) in bat.iter_mut()
     {
+        let delta = time_scale.delta(delta.value, entity, local);
+        let position = match bat.owner.try_safe() {
+            Some(bat) => bat.global_position(),
+            None => continue,
+        };
+
         match *state {
             BatState::IDLE => {
-                velocity.velocity =
-                    velocity.move_toward(Vector2::ZERO, friction.friction * delta.value);
+                velocity.velocity = velocity.move_toward(Vector2::ZERO, friction.friction * delta);
                 bat_idle_or_wander(delect_zone, &mut *state, &mut timer.0);
             }
             BatState::CHASE => bat_chase(
                 &mut *velocity,
                 acceleration,
-                delect_zone,
-                bat,
+                position,
+                delect_zone
+                    .player
+                    .and_then(|entity| player.get(entity).ok())
+                    .and_then(|player| player.owner.try_safe())
+                    .map(|player| player.global_position()),
                 &mut *state,
-                sprite,
-                &delta,
+                delta,
             ),
             BatState::WANDER => {
                 bat_idle_or_wander(delect_zone, &mut *state, &mut timer.0);
-                let direction = bat
-                    .owner
-                    .expect_safe()
-                    .global_position()
-                    .direction_to(wander_controller.target_position);
+                let direction = position.direction_to(wander_controller.target_position);
 
                 velocity.velocity = velocity.move_toward(
                     direction * acceleration.max_speed,
-                    acceleration.acceleration * delta.value,
+                    acceleration.acceleration * delta,
                 );
             }
         }
+        facing.look(velocity.velocity);
     }
 }
 
@@ -181,117 +234,93 @@ pub fn bat_idle_or_wander(delect_zone: &DelectionZone, state: &mut BatState, tim
 pub fn bat_chase(
     velocity: &mut Velocity,
     acceleration: &Acceleration,
-    delect_zone: &DelectionZone,
-    bat: &Bat,
+    position: Vector2,
+    player_pos: Option<Vector2>,
     state: &mut BatState,
-    sprite: &GodotObjRef<AnimatedSprite>,
-    delta: &PhysicsDelta,
+    delta: f32,
 ) {
-    if let Some(player) = delect_zone.player {
-        let player_pos = player.expect_safe().global_position();
-        let direction = bat
-            .owner
-            .expect_safe()
-            .global_position()
-            .direction_to(player_pos);
+    if let Some(player_pos) = player_pos {
+        let direction = position.direction_to(player_pos);
 
         velocity.velocity = velocity.move_toward(
             direction * acceleration.max_speed,
-            acceleration.acceleration * delta.value,
+            acceleration.acceleration * delta,
         );
     } else {
         *state = BatState::IDLE;
         velocity.velocity = Vector2::ZERO;
     }
-
-    sprite.expect_safe().set_flip_v(velocity.x < 0.0);
-}
-
-/// Bat Move System.
-/// This system is responsible for the bat's movement.
-/// The bat will move according to the velocity.
-pub fn bat_move_system(
-    mut query: Query<(&mut Velocity, &mut BatKnockback, &Bat, &SoftCollision)>,
-    delta: Res<PhysicsDelta>,
-) {
-    for (mut velocity, mut knockback, bat, soft_collision) in query.iter_mut() {
-        let bat = bat.owner.expect_safe();
-        knockback.0.vector = knockback
-            .0
-            .vector
-            .move_toward(Vector2::ZERO, 200. * delta.value);
-        bat.move_and_slide(knockback.0.vector, Vector2::ZERO, false, 4, FRAC_PI_4, true);
-
-        velocity.velocity += soft_collision.input_vector;
-
-        velocity.velocity =
-            bat.move_and_slide(velocity.velocity, Vector2::ZERO, false, 4, FRAC_PI_4, true);
-    }
 }
 
 /// Attack Bat System.
+/// Damages and knocks back a bat when a player hitbox enters its hurtbox.
 pub fn attack_bat_system(
     mut commands: Commands,
-    mut bat: Query<
-        (
-            Entity,
-            &HurtBox,
-            &mut BatKnockback,
-            &mut Stats,
-            &Bat,
-            &HitBox,
-        ),
-        Without<PlayerAttacking>,
-    >,
-    hitbox: Query<&HitBox, With<Player>>,
+    mut area_entered: EventReader<AreaEntered>,
+    mut damaged: EventWriter<Damaged>,
+    mut rng: ResMut<CombatRng>,
+    mut impulse: EventWriter<Impulse>,
+    mut bat: Query<(&HurtBox, &mut Stats, &Defense, &Bat)>,
+    player: Query<&HitBox, With<Player>>,
+    mut swing: Query<&mut Swing>,
+    tuning: Res<Tuning>,
 ) {
-    for (entity, hurtbox, mut knockback, mut stats, bat, bat_hitbox) in bat.iter_mut() {
-        let hurtbox_area = hurtbox.owner.expect_safe();
-        let bat_body = bat.owner.expect_safe();
+    for AreaEntered { a, b: entity } in area_entered.iter() {
+        let (hitbox, (hurtbox, mut stats, defense, bat)) =
+            match (player.get(*a), bat.get_mut(*entity)) {
+                (Ok(hitbox), Ok(bat)) => (hitbox, bat),
+                _ => continue,
+            };
 
-        for hitbox in hitbox.iter() {
-            let hitbox_area = hitbox.owner.expect_safe();
-            if hurtbox_area.overlaps_area(hitbox_area) {
-                stats.health -= bat_hitbox.damage;
-                knockback.0.vector = hitbox.knockback * 120.;
+        // already killed by an earlier hit this frame
+        if stats.health <= 0 {
+            continue;
+        }
+        if let Ok(mut swing) = swing.get_mut(*a) {
+            if !swing.land(*entity) {
+                continue;
+            }
+        }
 
-                let positon = bat_body.global_position();
-                let parent = bat_body
-                    .expect_tree()
-                    .current_scene()
-                    .unwrap()
-                    .expect_safe();
+        let bat_body = match bat.owner.try_safe() {
+            Some(bat_body) => bat_body,
+            None => continue,
+        };
 
-                commands.entity(entity).insert(PlayerAttacking);
+        let positon = bat_body.global_position();
 
-                // spawn the effect
-                add_effect(&mut commands, &hurtbox.hit_effect.effect, positon, parent);
+        let hit = formula::resolve(&hitbox.into(), Some(defense), &mut rng.0);
+        stats.health -= hit.amount;
+        damaged.send(Damaged {
+            target: *entity,
+            source: *a,
+            amount: hit.amount,
+            kind: hitbox.kind,
+            crit: hit.crit,
+            position: positon,
+        });
+        impulse.send(Impulse {
+            target: *entity,
+            vector: hitbox.knockback_direction(positon) * tuning.combat.knockback,
+        });
 
-                if stats.health <= 0 {
-                    add_effect(&mut commands, &bat.dead_effect.effect, positon, parent);
+        let parent = bat_body
+            .expect_tree()
+            .current_scene()
+            .and_then(|scene| scene.try_safe());
 
-                    commands.entity(entity).despawn();
-                    bat_body.queue_free();
-                }
-            }
+        // spawn the effect
+        if let Some(parent) = parent {
+            add_effect(&mut commands, &hurtbox.hit_effect.effect, positon, parent);
         }
-    }
-}
-
-/// Attack Bat System.
-pub fn attack_exit_bat_system(
-    mut commands: Commands,
-    mut enmemy: Query<(Entity, &HurtBox), With<PlayerAttacking>>,
-    hitbox: Query<&HitBox, With<Player>>,
-) {
-    for (entity, hurtbox) in enmemy.iter_mut() {
-        let hurtbox_area = hurtbox.owner.expect_safe();
 
-        for hitbox in hitbox.iter() {
-            let hitbox_area = hitbox.owner.expect_safe();
-            if !hurtbox_area.overlaps_area(hitbox_area) {
-                commands.entity(entity).remove::<PlayerAttacking>();
+        if stats.health <= 0 {
+            if let Some(parent) = parent {
+                add_effect(&mut commands, &bat.dead_effect.effect, positon, parent);
             }
+
+            commands.entity(*entity).despawn();
+            bat_body.queue_free();
         }
     }
 }
@@ -1,13 +1,13 @@
+use crate::animation::animation_system;
+use crate::bridge::{BodyEntered, BodyExited};
 use crate::delect_box::soft_collision::soft_collision_system;
-use crate::enemy::bat::{attack_bat_system, attack_exit_bat_system, bat_move_system, bat_system};
+use crate::enemy::bat::{attack_bat_system, bat_system, BatState};
 use crate::enemy::wander_controller::update_target_position_system;
 use crate::player::Player;
 use bevy::app::Plugin;
-use bevy::prelude::{Component, ParallelSystemDescriptorCoercion, Query};
+use bevy::prelude::{Component, Entity, EventReader, ParallelSystemDescriptorCoercion, Query};
 use gdnative::api::Area2D;
 use gdnative::prelude::*;
-use gdrust::ecs::engine_sync::stages::SyncStages;
-use gdrust::unsafe_functions::RefExt;
 
 pub mod bat;
 pub mod wander_controller;
@@ -15,22 +15,28 @@ pub mod wander_controller;
 #[derive(Component, Copy, Clone)]
 pub struct DelectionZone {
     pub owner: Ref<Area2D>,
-    pub player: Option<Ref<KinematicBody2D>>,
+    pub player: Option<Entity>,
 }
 
-fn zone_system(mut zone: Query<&mut DelectionZone>, player: Query<&Player>) {
-    for mut zone in zone.iter_mut() {
-        let zone_area = zone.owner.expect_safe();
-
-        if player.is_empty() {
-            zone.player = None;
+/// Zone System.
+/// Tracks the player entering and leaving each delection zone.
+fn zone_system(
+    mut body_entered: EventReader<BodyEntered>,
+    mut body_exited: EventReader<BodyExited>,
+    mut zone: Query<&mut DelectionZone>,
+    player: Query<&Player>,
+) {
+    for BodyEntered { a, b } in body_entered.iter() {
+        if let Ok(mut zone) = zone.get_mut(*a) {
+            if player.contains(*b) {
+                zone.player = Some(*b);
+            }
         }
+    }
 
-        for player in player.iter() {
-            let player = player.owner.expect_safe();
-            if zone_area.overlaps_body(player) {
-                zone.player = Some(player.claim());
-            } else if zone.player.is_some() {
+    for BodyExited { a, b } in body_exited.iter() {
+        if let Ok(mut zone) = zone.get_mut(*a) {
+            if zone.player == Some(*b) {
                 zone.player = None;
             }
         }
@@ -41,12 +47,11 @@ pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_system(zone_system.label("zone_system"))
+        app.add_system(zone_system.label("zone_system").after("node_signal"))
             .add_system(bat_system.after("zone_system"))
-            .add_system(soft_collision_system)
+            .add_system(animation_system::<BatState>)
+            .add_system(soft_collision_system.after("spatial_grid"))
             .add_system(update_target_position_system)
-            .add_system(attack_exit_bat_system)
-            .add_system(attack_bat_system)
-            .add_system_to_stage(SyncStages::UpdateBevyPhysics, bat_move_system);
+            .add_system(attack_bat_system.after("node_signal"));
     }
 }
@@ -1,9 +1,11 @@
-use bevy::prelude::{Component, Query, Res, Time, Timer};
+use bevy::prelude::{Component, Entity, Query, Res, Time, Timer};
 use gdnative::prelude::*;
 use gdrust::macros::*;
 use rand::{thread_rng, Rng};
 use std::ops::Range;
 
+use crate::time_scale::{LocalTimeScale, TimeScale};
+
 #[derive(Component, Clone)]
 pub struct WanderTimer(pub Timer);
 
@@ -15,7 +17,7 @@ pub struct WanderController {
     pub owner: Ref<Node2D>,
     #[export]
     #[default(32.)]
-    wander_range: f32,
+    pub wander_range: f32,
     pub start_position: Vector2,
     pub target_position: Vector2,
 }
@@ -30,23 +32,37 @@ impl WanderController {
 
 pub fn update_target_position_system(
     time: Res<Time>,
-    mut wander_controller: Query<(&mut WanderController, &mut WanderTimer)>,
+    time_scale: Res<TimeScale>,
+    mut wander_controller: Query<(
+        Entity,
+        &mut WanderController,
+        &mut WanderTimer,
+        Option<&LocalTimeScale>,
+    )>,
 ) {
     let mut rng = thread_rng();
 
-    for (mut wander_controller, mut timer) in wander_controller.iter_mut() {
-        timer.0.tick(time.delta());
+    for (entity, mut wander_controller, mut timer, local) in wander_controller.iter_mut() {
+        timer
+            .0
+            .tick(time_scale.duration(time.delta(), entity, local));
         if timer.0.just_finished() {
-            let target_vector = Vector2::new(
-                rng.gen_range(Range {
-                    start: -wander_controller.wander_range,
-                    end: wander_controller.wander_range,
-                }),
-                rng.gen_range(Range {
-                    start: -wander_controller.wander_range,
-                    end: wander_controller.wander_range,
-                }),
-            );
+            let range = wander_controller.wander_range;
+            // `gen_range` panics on an empty range
+            let target_vector = if range > 0. {
+                Vector2::new(
+                    rng.gen_range(Range {
+                        start: -range,
+                        end: range,
+                    }),
+                    rng.gen_range(Range {
+                        start: -range,
+                        end: range,
+                    }),
+                )
+            } else {
+                Vector2::ZERO
+            };
 
             wander_controller.target_position = wander_controller.start_position + target_vector;
         }
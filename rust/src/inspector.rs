@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use gdnative::prelude::*;
+pub use rpg_ecs_derive::Inspect;
+
+/// Godot's `PROPERTY_HINT_RANGE`.
+const PROPERTY_HINT_RANGE: i64 = 1;
+/// Godot's `PROPERTY_USAGE_DEFAULT`.
+const PROPERTY_USAGE_DEFAULT: i64 = 7;
+
+/// A field of a component shown in the inspector.
+pub struct Field {
+    pub name: &'static str,
+    pub variant_type: VariantType,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// A scalar type that can be edited in the inspector.
+pub trait FieldValue: FromVariant + ToVariant + Copy + PartialEq + Display {
+    const VARIANT_TYPE: VariantType;
+    fn clamp_to(self, min: f64, max: f64) -> Self;
+}
+
+impl FieldValue for i32 {
+    const VARIANT_TYPE: VariantType = VariantType::I64;
+    fn clamp_to(self, min: f64, max: f64) -> Self {
+        self.clamp(min as i32, max as i32)
+    }
+}
+
+impl FieldValue for f32 {
+    const VARIANT_TYPE: VariantType = VariantType::F64;
+    fn clamp_to(self, min: f64, max: f64) -> Self {
+        self.clamp(min as f32, max as f32)
+    }
+}
+
+/// A component exported to the inspector field by field.
+/// Implemented with `#[derive(Inspect)]`.
+pub trait Inspect {
+    fn fields(&self) -> &'static [Field];
+    fn get_field(&self, name: &str) -> Option<Variant>;
+    /// Set a field, clamping it to its range. Returns false for unknown fields.
+    fn set_field(&mut self, name: &str, value: &Variant, owner: &str) -> bool;
+    fn default_field(&self, name: &str) -> Option<Variant>;
+    /// Fix values that are inconsistent with each other.
+    fn validate(&mut self, _owner: &str) {}
+}
+
+/// Convert and clamp an inspector value, warning when it had to be fixed.
+pub fn clamp_field<T: FieldValue>(
+    owner: &str,
+    name: &str,
+    value: &Variant,
+    current: T,
+    min: f64,
+    max: f64,
+) -> T {
+    let value = match T::from_variant(value) {
+        Ok(value) => value,
+        Err(err) => {
+            godot_warn!("{}: invalid value for `{}`: {}", owner, name, err);
+            return current;
+        }
+    };
+
+    let clamped = value.clamp_to(min, max);
+    if clamped != value {
+        godot_warn!(
+            "{}: `{}` = {} is outside [{}, {}], clamped to {}",
+            owner,
+            name,
+            value,
+            min,
+            max,
+            clamped
+        );
+    }
+    clamped
+}
+
+fn split(property: &str) -> Option<(&str, &str)> {
+    let mut parts = property.splitn(2, '/');
+    Some((parts.next()?, parts.next()?))
+}
+
+/// Property list of every group, for a bundle's `_get_property_list`.
+pub fn property_list(groups: &[(&str, &dyn Inspect)]) -> VariantArray {
+    let list = VariantArray::new();
+
+    for (group, component) in groups {
+        for field in component.fields() {
+            let property = Dictionary::new();
+            property.insert("name", format!("{}/{}", group, field.name));
+            property.insert("type", field.variant_type as i64);
+            property.insert("hint", PROPERTY_HINT_RANGE);
+            property.insert("hint_string", format!("{},{}", field.min, field.max));
+            property.insert("usage", PROPERTY_USAGE_DEFAULT);
+            list.push(property.into_shared());
+        }
+    }
+
+    list.into_shared()
+}
+
+/// Value of `group/field`, for a bundle's `_get`.
+pub fn get_property(groups: &[(&str, &dyn Inspect)], property: &str) -> Variant {
+    split(property)
+        .and_then(|(name, field)| {
+            let (_, component) = groups.iter().find(|(group, _)| *group == name)?;
+            component.get_field(field)
+        })
+        .unwrap_or_else(Variant::nil)
+}
+
+/// Set `group/field`, for a bundle's `_set`.
+/// Also accepts a whole group as a Dictionary, the format older scenes saved.
+pub fn set_property(
+    groups: &mut [(&str, &mut dyn Inspect)],
+    property: &str,
+    value: &Variant,
+    owner: &str,
+) -> bool {
+    let (name, field) = match split(property) {
+        Some((name, field)) => (name, Some(field)),
+        None => (property, None),
+    };
+    let component = match groups.iter_mut().find(|(group, _)| *group == name) {
+        Some((_, component)) => component,
+        None => return false,
+    };
+
+    match field {
+        Some(field) => {
+            if !component.set_field(field, value, owner) {
+                godot_warn!("{}: unknown property `{}`", owner, property);
+                return false;
+            }
+        }
+        None => {
+            let dictionary = match Dictionary::from_variant(value) {
+                Ok(dictionary) => dictionary,
+                Err(_) => return false,
+            };
+            for (key, value) in dictionary.iter() {
+                let key = key.to_string();
+                if !component.set_field(&key, &value, owner) {
+                    godot_warn!("{}: unknown property `{}/{}`", owner, name, key);
+                }
+            }
+        }
+    }
+
+    component.validate(owner);
+    true
+}
+
+/// Default value of `group/field`, for a bundle's `property_get_revert`.
+pub fn default_property(groups: &[(&str, &dyn Inspect)], property: &str) -> Option<Variant> {
+    let (name, field) = split(property)?;
+    let (_, component) = groups.iter().find(|(group, _)| *group == name)?;
+    component.default_field(field)
+}
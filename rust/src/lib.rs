@@ -1,22 +1,53 @@
 #![feature(derive_default_enum)]
 
+mod animation;
+mod bridge;
+mod camera;
+mod combat;
 mod components;
 mod delect_box;
+mod dialogue;
 mod effect;
 mod enemy;
+mod inspector;
+mod movement;
 mod player;
+mod save;
+mod time_scale;
+mod transition;
+mod tuning;
 mod world;
 
+use crate::animation::AnimationPlugin;
+use crate::bridge::{BridgePlugin, SignalBridge};
+use crate::camera::rig::CameraRig;
+use crate::camera::room::Room;
+use crate::camera::CameraPlugin;
+use crate::combat::CombatPlugin;
 use crate::delect_box::hit_box::HitBox;
 use crate::delect_box::hurt_box::HurtBox;
 use crate::delect_box::soft_collision::SoftCollision;
+use crate::dialogue::npc::Npc;
+use crate::dialogue::ui::DialogueBox;
+use crate::dialogue::DialoguePlugin;
 use crate::effect::{Effect, EffectPlugin};
 use crate::enemy::bat::BatBundle;
 use crate::enemy::wander_controller::WanderController;
 use crate::enemy::EnemyPlugin;
+use crate::movement::MovementPlugin;
 use crate::player::{PlayerBundle, PlayerPlugin};
-use crate::world::grass::Grass;
+use crate::save::SavePlugin;
+use crate::time_scale::TimeScalePlugin;
+use crate::transition::door::Door;
+use crate::transition::fade::ScreenFade;
+use crate::transition::spawn_point::SpawnPoint;
+use crate::transition::TransitionPlugin;
+use crate::tuning::TuningPlugin;
+use crate::world::checkpoint::Checkpoint;
+use crate::world::destructible::Destructible;
 use crate::world::health::HealthBundle;
+use crate::world::health_bar::HealthBar;
+use crate::world::procedural_world::ProceduralWorld;
 use crate::world::world::WorldBundle;
 use crate::world::WorldPlugin;
 use bevy::app::App;
@@ -28,25 +59,46 @@ use gdrust::ecs::engine_controller::ECSController;
 struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(WorldPlugin)
+        app.add_plugin(BridgePlugin)
+            .add_plugin(AnimationPlugin)
+            .add_plugin(CombatPlugin)
+            .add_plugin(TimeScalePlugin)
+            .add_plugin(MovementPlugin)
+            .add_plugin(CameraPlugin)
+            .add_plugin(TuningPlugin)
+            .add_plugin(TransitionPlugin)
+            .add_plugin(SavePlugin)
+            .add_plugin(WorldPlugin)
             .add_plugin(EffectPlugin)
             .add_plugin(EnemyPlugin)
-            .add_plugin(PlayerPlugin);
+            .add_plugin(PlayerPlugin)
+            .add_plugin(DialoguePlugin);
     }
 }
 
 fn init(handle: InitHandle) {
     handle.add_class::<ECSController>();
+    handle.add_class::<SignalBridge>();
     handle.add_class::<HitBox>();
     handle.add_class::<HurtBox>();
     handle.add_class::<PlayerBundle>();
     handle.add_class::<BatBundle>();
-    handle.add_class::<Grass>();
+    handle.add_class::<Destructible>();
     handle.add_class::<WorldBundle>();
     handle.add_class::<HealthBundle>();
+    handle.add_class::<HealthBar>();
     handle.add_class::<SoftCollision>();
     handle.add_class::<WanderController>();
     handle.add_class::<Effect>();
+    handle.add_class::<Door>();
+    handle.add_class::<SpawnPoint>();
+    handle.add_class::<Checkpoint>();
+    handle.add_class::<Npc>();
+    handle.add_class::<DialogueBox>();
+    handle.add_class::<ScreenFade>();
+    handle.add_class::<ProceduralWorld>();
+    handle.add_class::<CameraRig>();
+    handle.add_class::<Room>();
 
     init_ecs(GamePlugin);
 }
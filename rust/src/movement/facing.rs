@@ -0,0 +1,95 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::{Changed, Component, Query, With};
+use gdnative::api::AnimationTree;
+use gdnative::prelude::*;
+use gdrust::ecs::engine_sync::components::GodotObjRef;
+
+use crate::animation::Animator;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::delect_box::hit_box::HitBox;
+use crate::player::Player;
+
+/// Facing Component.
+/// The direction an actor looks in, set by movement and AI.
+#[derive(Component, Clone, Copy)]
+pub struct Facing {
+    /// Last non-zero direction, normalized.
+    pub vector: Vector2,
+    /// Number of directions `direction` snaps to, 4 or 8.
+    pub directions: u8,
+}
+
+impl Default for Facing {
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+impl Facing {
+    pub fn new(directions: u8) -> Self {
+        Self {
+            vector: Vector2::new(0., 1.),
+            directions,
+        }
+    }
+
+    /// Look in `direction`, keeping the last one if it is zero.
+    pub fn look(&mut self, direction: Vector2) {
+        if direction != Vector2::ZERO {
+            self.vector = direction.normalized();
+        }
+    }
+
+    /// The facing snapped to the nearest of `directions` directions.
+    pub fn direction(&self) -> Vector2 {
+        let step = TAU / self.directions.max(1) as f32;
+        let angle = (self.vector.y.atan2(self.vector.x) / step).round() * step;
+        Vector2::new(angle.cos(), angle.sin())
+    }
+
+    pub fn is_left(&self) -> bool {
+        self.vector.x < 0.
+    }
+}
+
+/// Facing Blend Component.
+/// AnimationTree blend spaces whose `blend_position` follows the facing.
+#[derive(Component, Clone, Copy)]
+pub struct FacingBlend(pub &'static [&'static str]);
+
+/// Facing Blend System.
+pub fn facing_blend_system(
+    query: Query<(&Facing, &FacingBlend, &GodotObjRef<AnimationTree>), Changed<Facing>>,
+) {
+    for (facing, blend, animation_tree) in query.iter() {
+        let animation_tree = match animation_tree.try_safe() {
+            Some(animation_tree) => animation_tree,
+            None => continue,
+        };
+
+        let direction = facing.direction();
+        for name in blend.0 {
+            animation_tree.set(format!("parameters/{}/blend_position", name), direction);
+        }
+    }
+}
+
+/// Facing Sprite System.
+/// Flips sprites to look left or right.
+pub fn facing_sprite_system(query: Query<(&Facing, &Animator), Changed<Facing>>) {
+    for (facing, animator) in query.iter() {
+        animator.set_flip_h(facing.is_left());
+    }
+}
+
+/// Facing HitBox System.
+/// The player's sword knocks targets back in the facing direction, other
+/// hitboxes keep their own knockback.
+pub fn facing_hitbox_system(
+    mut query: Query<(&Facing, &mut HitBox), (Changed<Facing>, With<Player>)>,
+) {
+    for (facing, mut hitbox) in query.iter_mut() {
+        hitbox.knockback = facing.vector;
+    }
+}
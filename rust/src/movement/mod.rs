@@ -0,0 +1,171 @@
+use bevy::prelude::{
+    App, Component, CoreStage, Entity, EventReader, EventWriter, ParallelSystemDescriptorCoercion,
+    Plugin, Query, Res,
+};
+use gdnative::api::KinematicBody2D;
+use gdnative::prelude::*;
+use gdrust::ecs::engine_sync::resources::PhysicsDelta;
+use gdrust::ecs::engine_sync::stages::SyncStages;
+use gdrust::unsafe_functions::RefExt;
+use std::f64::consts::FRAC_PI_4;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::registry::NodeRegistry;
+use crate::components::{Knockback, Velocity};
+use crate::delect_box::soft_collision::SoftCollision;
+use crate::movement::facing::{facing_blend_system, facing_hitbox_system, facing_sprite_system};
+use crate::time_scale::{LocalTimeScale, TimeScale};
+
+pub mod facing;
+
+/// A single collision of a move step.
+#[derive(Clone, Copy)]
+pub struct SlideCollision {
+    pub normal: Vector2,
+    pub position: Vector2,
+    /// The entity backing the collider, if any.
+    pub collider: Option<Entity>,
+}
+
+/// Kinematic Body Component.
+/// Moved by the movement system, which records what it hit last step.
+#[derive(Component, Clone)]
+pub struct KinematicBody {
+    pub owner: Ref<KinematicBody2D>,
+    pub collisions: Vec<SlideCollision>,
+}
+
+impl KinematicBody {
+    pub fn new(owner: TRef<KinematicBody2D>) -> Self {
+        Self {
+            owner: owner.claim(),
+            collisions: Vec::new(),
+        }
+    }
+
+    /// Normal of the first wall hit last step.
+    pub fn wall_normal(&self) -> Option<Vector2> {
+        self.collisions.first().map(|collision| collision.normal)
+    }
+}
+
+/// External Force Component.
+/// Velocity added to every move step, e.g. wind or a conveyor.
+#[derive(Component, Default, Clone, Copy)]
+pub struct ExternalForce(pub Vector2);
+
+/// Sent for every collision of a move step.
+pub struct Collided {
+    pub entity: Entity,
+    pub collision: SlideCollision,
+}
+
+/// Pushes `target` by `vector`, scaled down by its mass.
+pub struct Impulse {
+    pub target: Entity,
+    pub vector: Vector2,
+}
+
+/// Impulse System.
+/// Adds impulses to the knockback of their targets.
+pub fn impulse_system(mut impulse: EventReader<Impulse>, mut knockback: Query<&mut Knockback>) {
+    for Impulse { target, vector } in impulse.iter() {
+        if let Ok(mut knockback) = knockback.get_mut(*target) {
+            knockback.apply(*vector);
+        }
+    }
+}
+
+/// Movement System.
+/// Moves every kinematic body once per physics frame by its velocity plus
+/// knockback, soft collision push and external force. Velocity and
+/// knockback slide along whatever the body hits.
+pub fn movement_system(
+    delta: Res<PhysicsDelta>,
+    time_scale: Res<TimeScale>,
+    registry: Res<NodeRegistry>,
+    mut collided: EventWriter<Collided>,
+    mut body: Query<(
+        Entity,
+        &mut KinematicBody,
+        &mut Velocity,
+        Option<&mut Knockback>,
+        Option<&SoftCollision>,
+        Option<&ExternalForce>,
+        Option<&LocalTimeScale>,
+    )>,
+) {
+    for (entity, mut body, mut velocity, mut knockback, soft_collision, force, local) in
+        body.iter_mut()
+    {
+        let scale = time_scale.factor(entity, local);
+        if scale <= 0. {
+            continue;
+        }
+        let owner = match body.owner.try_safe() {
+            Some(owner) => owner,
+            None => continue,
+        };
+
+        let mut motion = velocity.velocity;
+        if let Some(knockback) = knockback.as_deref_mut() {
+            knockback.tick(delta.value * scale);
+            motion += knockback.vector;
+        }
+        if let Some(soft_collision) = soft_collision {
+            motion += soft_collision.push;
+        }
+        if let Some(ExternalForce(force)) = force {
+            motion += *force;
+        }
+
+        owner.move_and_slide(motion * scale, Vector2::ZERO, false, 4, FRAC_PI_4, true);
+
+        body.collisions.clear();
+        for i in 0..owner.get_slide_count() {
+            let collision = match owner.get_slide_collision(i) {
+                Some(collision) => collision.expect_safe(),
+                None => continue,
+            };
+            let collision = SlideCollision {
+                normal: collision.normal(),
+                position: collision.position(),
+                collider: collision
+                    .collider()
+                    .and_then(|collider| registry.entity(collider.expect_safe().get_instance_id())),
+            };
+
+            velocity.velocity = slide(velocity.velocity, collision.normal);
+            if let Some(knockback) = knockback.as_deref_mut() {
+                knockback.vector = slide(knockback.vector, collision.normal);
+            }
+            body.collisions.push(collision);
+            collided.send(Collided { entity, collision });
+        }
+    }
+}
+
+/// Remove the part of `vector` going into a surface with `normal`.
+fn slide(vector: Vector2, normal: Vector2) -> Vector2 {
+    if vector.dot(normal) < 0. {
+        vector.slide(normal)
+    } else {
+        vector
+    }
+}
+
+pub struct MovementPlugin;
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Impulse>()
+            .add_event::<Collided>()
+            .add_system(impulse_system)
+            .add_system_to_stage(CoreStage::PostUpdate, facing_blend_system)
+            .add_system_to_stage(CoreStage::PostUpdate, facing_sprite_system)
+            .add_system_to_stage(CoreStage::PostUpdate, facing_hitbox_system)
+            .add_system_to_stage(
+                SyncStages::UpdateBevyPhysics,
+                movement_system.label("movement"),
+            );
+    }
+}
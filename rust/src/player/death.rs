@@ -0,0 +1,162 @@
+use bevy::prelude::{
+    Changed, Commands, Component, Entity, EventWriter, Query, Res, Time, Timer, With, Without,
+};
+use gdnative::prelude::*;
+use gdrust::unsafe_functions::NodeTreeExt;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::components::{Inventory, Knockback, Stats};
+use crate::delect_box::hurt_box::HurtBox;
+use crate::effect::{add_effect, PlayerDeadEffect};
+use crate::player::{Player, PlayerFsm, PlayerState};
+use crate::time_scale::{LocalTimeScale, TimeScale};
+use crate::tuning::Tuning;
+use crate::world::health::ChangeHealth;
+
+/// Respawn Point Component.
+/// Where the player comes back after dying, moved by checkpoints.
+#[derive(Component, Clone, Copy)]
+pub struct RespawnPoint(pub Vector2);
+
+/// Dying Component.
+/// Counts down to the respawn of a dead player.
+#[derive(Component, Clone)]
+pub struct Dying(pub Timer);
+
+/// Sent when the player dies, for the camera and UI.
+pub struct PlayerDied {
+    pub position: Vector2,
+}
+
+/// Sent when the player comes back at its respawn point.
+pub struct PlayerRespawned {
+    pub position: Vector2,
+}
+
+/// Enable or disable a hurtbox for enemy hitboxes.
+fn set_hurtbox(hurtbox: &HurtBox, enabled: bool) {
+    if let Some(hurtbox) = hurtbox.owner.try_safe() {
+        hurtbox.set_deferred("monitorable", enabled);
+    }
+}
+
+/// Player No Health System.
+/// Kills the player once its health runs out.
+pub fn player_no_health_system(
+    mut player: Query<(&Stats, &mut PlayerFsm, &PlayerState), With<Player>>,
+) {
+    for (stats, mut fsm, state) in player.iter_mut() {
+        if stats.health <= 0 && *state != PlayerState::DEAD {
+            fsm.request(*state, PlayerState::DEAD);
+        }
+    }
+}
+
+/// Player Death System.
+/// Plays the death effect, turns off the hurtbox and starts the respawn
+/// countdown. The Death animation fades the player out meanwhile.
+pub fn player_death_system(
+    mut commands: Commands,
+    tuning: Res<Tuning>,
+    mut died: EventWriter<PlayerDied>,
+    player: Query<
+        (Entity, &Player, &PlayerState, &HurtBox, &PlayerDeadEffect),
+        (Changed<PlayerState>, Without<Dying>),
+    >,
+) {
+    for (entity, player, state, hurtbox, dead_effect) in player.iter() {
+        if *state != PlayerState::DEAD {
+            continue;
+        }
+        let owner = match player.owner.try_safe() {
+            Some(owner) => owner,
+            None => continue,
+        };
+        let position = owner.global_position();
+
+        let parent = owner
+            .expect_tree()
+            .current_scene()
+            .and_then(|scene| scene.try_safe());
+        if let Some(parent) = parent {
+            add_effect(&mut commands, &dead_effect.effect, position, parent);
+        }
+        set_hurtbox(hurtbox, false);
+
+        commands.entity(entity).insert(Dying(Timer::from_seconds(
+            tuning.death.respawn_delay,
+            false,
+        )));
+        died.send(PlayerDied { position });
+    }
+}
+
+/// Player Respawn System.
+/// Brings dead players back at their respawn point, minus the death
+/// penalties from the tuning.
+pub fn player_respawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    tuning: Res<Tuning>,
+    mut health_event: EventWriter<ChangeHealth>,
+    mut respawned: EventWriter<PlayerRespawned>,
+    mut player: Query<(
+        Entity,
+        &Player,
+        &mut Dying,
+        &mut PlayerFsm,
+        &mut Stats,
+        &mut Inventory,
+        &mut Knockback,
+        &RespawnPoint,
+        &HurtBox,
+        Option<&LocalTimeScale>,
+    )>,
+) {
+    for (
+        entity,
+        player,
+        mut dying,
+        mut fsm,
+        mut stats,
+        mut inventory,
+        mut knockback,
+        respawn,
+        hurtbox,
+        local,
+    ) in player.iter_mut()
+    {
+        dying
+            .0
+            .tick(time_scale.duration(time.delta(), entity, local));
+        if !dying.0.finished() {
+            continue;
+        }
+
+        let death = tuning.death;
+        stats.health = ((stats.max_health as f32 * death.health).round() as i32)
+            .clamp(1, stats.max_health.max(1));
+        for count in inventory.items.values_mut() {
+            *count -= (*count as f32 * death.item_loss.clamp(0., 1.)) as u32;
+        }
+        inventory.items.retain(|_, count| *count > 0);
+        knockback.vector = Vector2::ZERO;
+
+        if let Some(owner) = player.owner.try_safe() {
+            owner.set_global_position(respawn.0);
+            // undo the fade of the Death animation
+            owner.set_modulate(Color::from_rgb(1., 1., 1.));
+        }
+        set_hurtbox(hurtbox, true);
+        fsm.respawn();
+
+        commands.entity(entity).remove::<Dying>();
+        health_event.send(ChangeHealth {
+            health: stats.health,
+        });
+        respawned.send(PlayerRespawned {
+            position: respawn.0,
+        });
+    }
+}
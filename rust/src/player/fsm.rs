@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::{Commands, Component, Entity, Query, Res, Time, Timer, With};
+use gdnative::prelude::*;
+
+use crate::animation::Animator;
+use crate::components::{Roll, RollCooldown, Velocity};
+use crate::delect_box::hit_box::{HitBox, Swing};
+use crate::movement::facing::Facing;
+use crate::player::{Player, PlayerState};
+use crate::time_scale::{LocalTimeScale, TimeScale};
+
+/// How long the player staggers after a hit.
+const HURT_DURATION: f32 = 0.3;
+/// Number of transitions kept in the log.
+const LOG_SIZE: usize = 16;
+
+impl PlayerState {
+    /// Of several requests in one frame, the highest priority wins.
+    pub fn priority(&self) -> u8 {
+        match self {
+            PlayerState::MOVE => 0,
+            PlayerState::INTERACT => 1,
+            PlayerState::ATTACK => 2,
+            PlayerState::ROLL => 3,
+            PlayerState::HURT => 4,
+            PlayerState::DEAD => 5,
+        }
+    }
+
+    /// Whether the player may go from this state to `to`.
+    pub fn allows(&self, to: PlayerState) -> bool {
+        match (*self, to) {
+            (PlayerState::DEAD, _) => false,
+            (_, PlayerState::DEAD) | (_, PlayerState::HURT) => true,
+            (PlayerState::MOVE, _) => true,
+            // roll cancels are gated by `Roll::can_cancel`
+            (PlayerState::ROLL, PlayerState::ATTACK) => true,
+            // timed states end on their own, the others when released
+            (_, PlayerState::MOVE) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A state change, for debugging.
+#[derive(Clone, Copy)]
+pub struct Transition {
+    pub from: PlayerState,
+    pub to: PlayerState,
+    /// Seconds since startup.
+    pub at: f64,
+}
+
+/// Player State Machine Component.
+/// Collects transition requests, times the current state and keeps a log of
+/// recent transitions. `PlayerState` holds the current state.
+#[derive(Component, Default, Clone)]
+pub struct PlayerFsm {
+    next: Option<PlayerState>,
+    /// Runs while in a state that ends on its own.
+    timer: Option<Timer>,
+    log: VecDeque<Transition>,
+}
+
+impl PlayerFsm {
+    /// Ask to go from `from` to `to` on the next update.
+    pub fn request(&mut self, from: PlayerState, to: PlayerState) {
+        if !from.allows(to) || from == to {
+            return;
+        }
+        if self
+            .next
+            .map_or(true, |next| to.priority() > next.priority())
+        {
+            self.next = Some(to);
+        }
+    }
+
+    /// Leave DEAD, which no request can.
+    pub fn respawn(&mut self) {
+        self.next = Some(PlayerState::MOVE);
+    }
+
+    /// How far through the current state the player is, from 0 to 1.
+    /// Always 0 in states without a timer.
+    pub fn progress(&self) -> f32 {
+        self.timer.as_ref().map_or(0., |timer| timer.percent())
+    }
+
+    pub fn log(&self) -> impl Iterator<Item = &Transition> {
+        self.log.iter()
+    }
+}
+
+/// What enter and exit handlers act on.
+struct Actor<'a> {
+    entity: Entity,
+    velocity: &'a mut Velocity,
+    roll: &'a Roll,
+    roll_cooldown: &'a mut RollCooldown,
+    facing: &'a Facing,
+    animator: &'a Animator,
+    hitbox: &'a HitBox,
+}
+
+/// Set the player up for `state`, returning how long it lasts if it ends on
+/// its own.
+fn enter(state: PlayerState, actor: &mut Actor, commands: &mut Commands) -> Option<f32> {
+    match state {
+        PlayerState::MOVE => None,
+        PlayerState::ATTACK => {
+            actor.velocity.velocity = Vector2::ZERO;
+            commands.entity(actor.entity).insert(Swing::default());
+            Some(state_duration(actor.animator, "Attack"))
+        }
+        PlayerState::ROLL => {
+            actor.velocity.velocity = actor.facing.vector * actor.roll.speed(0.);
+            Some(state_duration(actor.animator, "Roll"))
+        }
+        PlayerState::HURT => {
+            actor.velocity.velocity = Vector2::ZERO;
+            Some(HURT_DURATION)
+        }
+        PlayerState::DEAD | PlayerState::INTERACT => {
+            actor.velocity.velocity = Vector2::ZERO;
+            None
+        }
+    }
+}
+
+/// Clean up after `state`.
+fn exit(state: PlayerState, actor: &mut Actor, commands: &mut Commands) {
+    match state {
+        PlayerState::ATTACK => {
+            actor.hitbox.set_active(false);
+            commands.entity(actor.entity).remove::<Swing>();
+        }
+        PlayerState::ROLL => {
+            actor.velocity.velocity = Vector2::ZERO;
+            actor.roll_cooldown.0 = actor.roll.cooldown;
+        }
+        _ => {}
+    }
+}
+
+/// Length of the animation of a timed state.
+fn state_duration(animator: &Animator, animation: &str) -> f32 {
+    animator.length(animation).unwrap_or_else(|| {
+        godot_warn!("Player has no `{}` animation", animation);
+        0.
+    })
+}
+
+/// Player Fsm System.
+/// Ticks the state timer, ending timed states, and applies the winning
+/// request through the exit and enter handlers.
+pub fn player_fsm_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut player: Query<
+        (
+            Entity,
+            &mut PlayerFsm,
+            &mut PlayerState,
+            &mut Velocity,
+            &Roll,
+            &mut RollCooldown,
+            &Facing,
+            &Animator,
+            &HitBox,
+            Option<&LocalTimeScale>,
+        ),
+        With<Player>,
+    >,
+) {
+    for (
+        entity,
+        mut fsm,
+        mut state,
+        mut velocity,
+        roll,
+        mut roll_cooldown,
+        facing,
+        animator,
+        hitbox,
+        local,
+    ) in player.iter_mut()
+    {
+        let delta = time_scale.duration(time.delta(), entity, local);
+        roll_cooldown.0 = (roll_cooldown.0 - delta.as_secs_f32()).max(0.);
+
+        let finished = fsm.timer.as_mut().map_or(false, |timer| {
+            timer.tick(delta);
+            timer.finished()
+        });
+        if finished {
+            fsm.request(*state, PlayerState::MOVE);
+        }
+
+        let next = match fsm.next.take() {
+            Some(next) => next,
+            None => continue,
+        };
+
+        let mut actor = Actor {
+            entity,
+            velocity: &mut *velocity,
+            roll,
+            roll_cooldown: &mut *roll_cooldown,
+            facing,
+            animator,
+            hitbox,
+        };
+        exit(*state, &mut actor, &mut commands);
+        fsm.timer = enter(next, &mut actor, &mut commands)
+            .map(|duration| Timer::from_seconds(duration, false));
+
+        if fsm.log.len() == LOG_SIZE {
+            fsm.log.pop_front();
+        }
+        fsm.log.push_back(Transition {
+            from: *state,
+            to: next,
+            at: time.seconds_since_startup(),
+        });
+        *state = next;
+    }
+}
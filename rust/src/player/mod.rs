@@ -1,27 +1,33 @@
+use crate::animation::animation_system;
+pub(crate) use crate::player::death::*;
+pub(crate) use crate::player::fsm::*;
 pub(crate) use crate::player::player::*;
-use bevy::prelude::{App, ParallelSystemDescriptorCoercion, Plugin, SystemSet};
+use bevy::prelude::{App, ParallelSystemDescriptorCoercion, Plugin};
 use gdrust::ecs::engine_sync::stages::SyncStages;
 
+mod death;
+mod fsm;
 mod player;
 
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(player_state_system)
-            .add_system(player_timer_system)
-            .add_system(player_move_system)
-            .add_system_set(
-                SystemSet::new()
-                    .with_system(attack_player_system)
-                    .with_system(attack_player_exit_system),
+        app.add_event::<PlayerDied>()
+            .add_event::<PlayerRespawned>()
+            .add_system(player_input_system.before("player_fsm"))
+            .add_system(player_fsm_system.label("player_fsm"))
+            .add_system(player_move_system.after("player_fsm"))
+            .add_system(player_death_system.after("player_fsm"))
+            .add_system(player_respawn_system.before("player_fsm"))
+            .add_system(animation_system::<PlayerState>)
+            .add_system(
+                attack_player_system
+                    .after("node_signal")
+                    .before("player_fsm"),
             )
             .add_system_to_stage(
                 SyncStages::UpdateBevyPhysics,
-                player_movement_system.label("player_movement"),
-            )
-            .add_system_to_stage(
-                SyncStages::UpdateBevyPhysics,
-                player_no_health_system.after("player_movement"),
+                player_no_health_system.after("movement"),
             );
     }
 }
@@ -1,44 +1,67 @@
-use std::f64::consts::FRAC_PI_4;
-
 use bevy::prelude::{
-    Bundle, Commands, Component, Entity, EventWriter, Query, Res, Time, Timer, With, Without,
+    Bundle, Component, DetectChanges, Entity, EventReader, EventWriter, Query, Res, ResMut, With,
+    World,
 };
 use defaults::Defaults;
-use gdnative::api::{
-    AnimationNodeStateMachinePlayback, AnimationPlayer, AnimationTree, CollisionShape2D,
-    KinematicBody2D,
-};
+use gdnative::api::{AnimationPlayer, AnimationTree, KinematicBody2D};
 use gdnative::prelude::*;
 use gdrust::ecs::app::with_world;
-use gdrust::ecs::engine_sync::components::{GodotObjRef, PlayingGame};
 use gdrust::ecs::engine_sync::resources::PhysicsDelta;
 use gdrust::macros::*;
 use gdrust::unsafe_functions::{NodeExt, RefExt};
 
-use crate::components::{Acceleration, Friction, Roll, Stats, Velocity};
+use crate::animation::{send_animation_event, AnimationState, Animator};
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::registry::NodeRegistry;
+use crate::bridge::AreaEntered;
+use crate::combat::{formula, CombatRng, Damaged};
+use crate::components::{
+    Acceleration, Defense, Friction, Inventory, Knockback, Roll, RollCooldown, Stats, Velocity,
+};
 use crate::delect_box::hit_box::HitBox;
 use crate::delect_box::hurt_box::HurtBox;
+use crate::effect::PlayerDeadEffect;
 use crate::enemy::bat::Bat;
+use crate::inspector::{default_property, get_property, property_list, set_property, Inspect};
+use crate::movement::facing::{Facing, FacingBlend};
+use crate::movement::{Impulse, KinematicBody};
+use crate::player::death::RespawnPoint;
+use crate::player::fsm::{PlayerFsm, Transition};
+use crate::time_scale::{LocalTimeScale, TimeScale};
+use crate::transition::Travelling;
+use crate::tuning::{Tuning, TuningPreset};
 use crate::world::health::ChangeHealth;
 
-const ATTACK_ANIMATION_LEN: f32 = 0.4;
-const ROLL_ANIMATION_LEN: f32 = 0.5;
+/// A roll ends on walls facing it at most this far from head-on, as a cosine.
+const ROLL_WALL_DOT: f32 = 0.7;
 
 /// player state.
 /// This is the state of the player.
 /// It is used to determine the player's state.
-#[derive(Component, Defaults, Clone, Copy, Eq, PartialEq)]
+#[derive(Component, Defaults, Clone, Copy, Eq, PartialEq, Debug)]
 #[def = "MOVE"]
 pub enum PlayerState {
     MOVE,
     ATTACK,
     ROLL,
+    HURT,
+    DEAD,
+    INTERACT,
 }
 
-#[derive(Component, Default, Clone, Copy)]
-pub struct PlayerBeenAttack;
-#[derive(Component, Default, Clone, Copy)]
-pub struct PlayerAttacking;
+impl AnimationState for PlayerState {
+    fn animation(&self, velocity: Vector2) -> &'static str {
+        match self {
+            PlayerState::MOVE if velocity == Vector2::ZERO => "Idle",
+            PlayerState::MOVE => "Run",
+            PlayerState::ATTACK => "Attack",
+            PlayerState::ROLL => "Roll",
+            PlayerState::DEAD => "Death",
+            PlayerState::HURT | PlayerState::INTERACT => "Idle",
+        }
+    }
+}
 
 /// Player Component.
 /// This is the component of the player.
@@ -54,15 +77,19 @@ pub struct PlayerBundle {
     #[default(Player{owner: _owner.claim()})]
     player: Player,
     state: PlayerState,
-    #[export]
     stats: Stats,
+    defense: Defense,
+    knockback: Knockback,
+    #[default(Facing::new(4))]
+    facing: Facing,
     velocity: Velocity,
-    #[export]
     acceleration: Acceleration,
-    #[export]
     friction: Friction,
-    #[export]
     roll: Roll,
+    roll_cooldown: RollCooldown,
+    inventory: Inventory,
+    #[export]
+    preset: TuningPreset,
 }
 
 #[methods]
@@ -70,74 +97,164 @@ impl PlayerBundle {
     #[export]
     fn _ready(&self, owner: TRef<KinematicBody2D>) {
         let animation_tree = owner.expect_node::<AnimationTree>("AnimationTree");
-        let animation_player = owner.expect_node::<AnimationPlayer>("AnimationPlayer");
 
         animation_tree.set_active(true);
 
-        owner
-            .expect_node::<CollisionShape2D>("HixboxPivot/SwordHitbox/CollisionShape2D")
-            .set_disabled(true);
-
-        // Add player to ECS.
+        // Add player to ECS, or attach it to the player entity that left the
+        // previous scene.
         with_world(|w| {
-            let animation_state = animation_tree
-                .get("parameters/playback")
-                .try_to_object::<AnimationNodeStateMachinePlayback>()
-                .expect("Could not get AnimationNodeStateMachinePlayback");
+            let travelling = w
+                .query_filtered::<Entity, With<Travelling>>()
+                .iter(w)
+                .next();
+            let tuned_hitbox = travelling.and_then(|entity| w.get::<HitBox>(entity).copied());
+
+            let player = match travelling {
+                Some(entity) => {
+                    w.entity_mut(entity).remove::<Travelling>();
+                    GodotEntity::attach(w, entity, owner).insert(Player {
+                        owner: owner.claim(),
+                    })
+                }
+                None => GodotEntity::spawn(w, owner)
+                    .insert_bundle(self.clone())
+                    .insert(FacingBlend(&["Idle", "Run", "Attack", "Roll"]))
+                    .insert(PlayerFsm::default())
+                    .insert(PlayerDeadEffect::default())
+                    .insert(RespawnPoint(owner.global_position())),
+            };
+            let entity = player.id();
+            let player = player
+                .insert(KinematicBody::new(owner))
+                .child::<HitBox>("HixboxPivot/SwordHitbox")
+                .child::<HurtBox>("Hurtbox")
+                .node_ref::<AnimationTree>("AnimationTree")
+                .node_ref::<AnimationPlayer>("AnimationPlayer");
+            if let Some(animator) = Animator::tree(animation_tree) {
+                player.insert(animator);
+            }
 
-            w.spawn()
-                .insert_bundle(self.clone())
-                .insert(
-                    owner
-                        .expect_instance::<HitBox>("HixboxPivot/SwordHitbox")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(
-                    owner
-                        .expect_instance::<HurtBox>("Hurtbox")
-                        .map(|h, _| h.clone())
-                        .unwrap(),
-                )
-                .insert(GodotObjRef::new(animation_tree.claim()))
-                .insert(GodotObjRef::new(animation_player.claim()))
-                .insert(GodotObjRef::new(animation_state))
-                .insert(Velocity::default())
-                .insert(PlayingGame);
+            if let Some(tuned_hitbox) = tuned_hitbox {
+                carry_over(w, entity, tuned_hitbox);
+            }
         });
     }
-}
-
-/// Player state system.
-/// This system is used to determine the player's state.
-pub fn player_state_system(mut player: Query<&mut PlayerState, With<Player>>) {
-    let input = Input::godot_singleton();
 
-    for mut state in player.iter_mut() {
-        if *state == PlayerState::MOVE {
-            if input.is_action_just_pressed("attack", false) {
-                *state = PlayerState::ATTACK;
-            }
-            if input.is_action_just_pressed("roll", false) {
-                *state = PlayerState::ROLL;
+    /// Recent state transitions, oldest first, for the remote debugger.
+    #[export]
+    fn transition_log(&self, owner: TRef<KinematicBody2D>) -> String {
+        let mut log = String::new();
+        with_world(|w| {
+            let entity = match w
+                .get_resource::<NodeRegistry>()
+                .and_then(|registry| registry.entity(owner.get_instance_id()))
+            {
+                Some(entity) => entity,
+                None => return,
+            };
+            if let Some(fsm) = w.get::<PlayerFsm>(entity) {
+                for Transition { from, to, at } in fsm.log() {
+                    log += &format!("{:.2}s {:?} -> {:?}\n", at, from, to);
+                }
             }
-        }
+        });
+        log
+    }
+
+    /// Called by method tracks of the AnimationPlayer.
+    #[export]
+    fn animation_event(&self, owner: TRef<KinematicBody2D>, name: String) {
+        send_animation_event(owner.get_instance_id(), name);
+    }
+
+    fn groups(&self) -> [(&'static str, &dyn Inspect); 6] {
+        [
+            ("stats", &self.stats),
+            ("defense", &self.defense),
+            ("knockback", &self.knockback),
+            ("acceleration", &self.acceleration),
+            ("friction", &self.friction),
+            ("roll", &self.roll),
+        ]
+    }
+
+    fn groups_mut(&mut self) -> [(&'static str, &mut dyn Inspect); 6] {
+        [
+            ("stats", &mut self.stats),
+            ("defense", &mut self.defense),
+            ("knockback", &mut self.knockback),
+            ("acceleration", &mut self.acceleration),
+            ("friction", &mut self.friction),
+            ("roll", &mut self.roll),
+        ]
+    }
+
+    #[export]
+    fn _get_property_list(&self, _owner: TRef<KinematicBody2D>) -> VariantArray {
+        property_list(&self.groups())
+    }
+
+    #[export]
+    fn _get(&self, _owner: TRef<KinematicBody2D>, property: String) -> Variant {
+        get_property(&self.groups(), &property)
+    }
+
+    #[export]
+    fn _set(&mut self, owner: TRef<KinematicBody2D>, property: String, value: Variant) -> bool {
+        let owner = owner.name().to_string();
+        set_property(&mut self.groups_mut(), &property, &value, &owner)
+    }
+
+    #[export]
+    fn property_can_revert(&self, _owner: TRef<KinematicBody2D>, property: String) -> bool {
+        default_property(&self.groups(), &property).is_some()
+    }
+
+    #[export]
+    fn property_get_revert(&self, _owner: TRef<KinematicBody2D>, property: String) -> Variant {
+        default_property(&self.groups(), &property).unwrap_or_else(Variant::nil)
+    }
+}
+
+/// Carry the state of a player entity's old nodes over to the new ones.
+fn carry_over(w: &mut World, entity: Entity, tuned_hitbox: HitBox) {
+    // the hitbox of the new node has its scene values, not the tuned ones
+    if let Some(mut hitbox) = w.get_mut::<HitBox>(entity) {
+        *hitbox = HitBox {
+            owner: hitbox.owner,
+            ..tuned_hitbox
+        };
+    }
+    // point the blend spaces of the new animation tree the same way
+    if let Some(mut facing) = w.get_mut::<Facing>(entity) {
+        facing.set_changed();
     }
 }
 
-pub fn player_timer_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut player: Query<(Entity, &mut Velocity, &mut Timer, &mut PlayerState), With<Player>>,
+/// Player Input System.
+/// Requests state changes from input.
+pub fn player_input_system(
+    mut player: Query<(&mut PlayerFsm, &PlayerState, &Roll, &RollCooldown), With<Player>>,
 ) {
-    for (entity, mut velocity, mut timer, mut state) in player.iter_mut() {
-        timer.tick(time.delta());
-        if timer.finished() {
-            if *state == PlayerState::ROLL {
-                velocity.velocity = Vector2::ZERO;
+    let input = Input::godot_singleton();
+
+    for (mut fsm, state, roll, roll_cooldown) in player.iter_mut() {
+        match *state {
+            PlayerState::MOVE => {
+                if input.is_action_just_pressed("attack", false) {
+                    fsm.request(*state, PlayerState::ATTACK);
+                }
+                if input.is_action_just_pressed("roll", false) && roll_cooldown.0 <= 0. {
+                    fsm.request(*state, PlayerState::ROLL);
+                }
+            }
+            PlayerState::ROLL => {
+                if input.is_action_just_pressed("attack", false) && roll.can_cancel(fsm.progress())
+                {
+                    fsm.request(*state, PlayerState::ATTACK);
+                }
             }
-            commands.entity(entity).remove::<Timer>();
-            *state = PlayerState::MOVE;
+            _ => {}
         }
     }
 }
@@ -145,82 +262,68 @@ pub fn player_timer_system(
 /// Player Move System.
 /// This system is used to change player's velocity.
 pub fn player_move_system(
-    mut commands: Commands,
     delta: Res<PhysicsDelta>,
+    time_scale: Res<TimeScale>,
     mut player: Query<
         (
             Entity,
-            &GodotObjRef<AnimationTree>,
-            &GodotObjRef<AnimationNodeStateMachinePlayback>,
+            &mut PlayerFsm,
             &mut Velocity,
-            &mut HitBox,
+            &mut Facing,
             &Acceleration,
             &Friction,
-            &mut Roll,
+            &Roll,
             &PlayerState,
-            Option<&Timer>,
+            &KinematicBody,
+            Option<&LocalTimeScale>,
         ),
         With<Player>,
     >,
 ) {
     for (
         entity,
-        animation_tree,
-        animation_state,
+        mut fsm,
         mut velocity,
-        mut hitbox,
+        mut facing,
         acceleration,
         friction,
-        mut roll,
+        roll,
         state,
-        timer,
+        body,
+        local,
     ) in player.iter_mut()
     {
         match *state {
             PlayerState::MOVE => player_move(
-                animation_tree.expect_safe(),
-                animation_state.expect_safe(),
-                &mut *hitbox,
-                &mut *roll,
+                &mut *facing,
                 &mut *velocity,
                 acceleration,
                 friction,
-                &delta,
+                time_scale.delta(delta.value, entity, local),
             ),
-            PlayerState::ATTACK => {
-                if timer.is_none() {
-                    player_attack(
-                        &mut commands,
-                        &entity,
-                        &mut *velocity,
-                        animation_state.expect_safe(),
-                    )
-                }
-            }
             PlayerState::ROLL => {
-                if timer.is_none() {
-                    player_roll(
-                        &mut commands,
-                        &entity,
-                        &mut *velocity,
-                        animation_state.expect_safe(),
-                        &mut *roll,
-                    )
+                velocity.velocity = facing.vector * roll.speed(fsm.progress());
+
+                // rolling into a wall ends the roll
+                if body
+                    .collisions
+                    .iter()
+                    .any(|collision| collision.normal.dot(facing.vector) < -ROLL_WALL_DOT)
+                {
+                    fsm.request(*state, PlayerState::MOVE);
                 }
             }
+            _ => {}
         }
     }
 }
 
 fn player_move(
-    animation_tree: TRef<AnimationTree>,
-    animation_state: TRef<AnimationNodeStateMachinePlayback>,
-    hitbox: &mut HitBox,
-    roll: &mut Roll,
+    facing: &mut Facing,
     velocity: &mut Velocity,
     acceleration: &Acceleration,
     friction: &Friction,
-    delta: &PhysicsDelta,
+    delta: f32,
 ) {
     let input = Input::godot_singleton();
     let mut input_vector = Vector2::new(
@@ -232,129 +335,59 @@ fn player_move(
 
     if input_vector != Vector2::ZERO {
         input_vector = input_vector.normalized();
-        hitbox.knockback = input_vector;
-        roll.roll_velocity = input_vector;
-
-        animation_tree.set("parameters/Idle/blend_position", input_vector);
-        animation_tree.set("parameters/Run/blend_position", input_vector);
-        animation_tree.set("parameters/Attack/blend_position", input_vector);
-        animation_tree.set("parameters/Roll/blend_position", input_vector);
-
-        animation_state.travel("Run");
-
+        facing.look(input_vector);
         velocity.velocity = velocity.move_toward(
             input_vector * acceleration.max_speed,
-            acceleration.acceleration * delta.value,
+            acceleration.acceleration * delta,
         );
     } else {
-        animation_state.travel("Idle");
-        velocity.velocity = velocity.move_toward(Vector2::ZERO, friction.friction * delta.value);
-    }
-}
-
-/// Player Attack System.
-/// This system is used to attack the player.
-pub fn player_attack(
-    commands: &mut Commands,
-    entity: &Entity,
-    velocity: &mut Velocity,
-    animation_state: TRef<AnimationNodeStateMachinePlayback>,
-) {
-    velocity.velocity = Vector2::ZERO;
-    animation_state.travel("Attack");
-
-    commands
-        .entity(*entity)
-        .insert(Timer::from_seconds(ATTACK_ANIMATION_LEN, false));
-}
-
-/// Player Roll System.
-/// This system is used to roll the player.
-pub fn player_roll(
-    commands: &mut Commands,
-    entity: &Entity,
-    velocity: &mut Velocity,
-    animation_state: TRef<AnimationNodeStateMachinePlayback>,
-    roll: &Roll,
-) {
-    velocity.velocity = roll.roll_velocity * roll.roll_speed;
-    animation_state.travel("Roll");
-
-    commands
-        .entity(*entity)
-        .insert(Timer::from_seconds(ROLL_ANIMATION_LEN, false));
-}
-
-/// Player Move System.
-/// This system is used to move the player.
-pub fn player_movement_system(mut query: Query<(&mut Velocity, &Player)>) {
-    for (mut velocity, player) in query.iter_mut() {
-        velocity.velocity = player.owner.expect_safe().move_and_slide(
-            velocity.velocity,
-            Vector2::ZERO,
-            false,
-            4,
-            FRAC_PI_4,
-            true,
-        );
+        velocity.velocity = velocity.move_toward(Vector2::ZERO, friction.friction * delta);
     }
 }
 
+/// Attack Player System.
+/// Damages the player when a bat hitbox enters the player's hurtbox, unless
+/// it is already dead.
 pub fn attack_player_system(
-    mut commands: Commands,
+    mut area_entered: EventReader<AreaEntered>,
     mut event: EventWriter<ChangeHealth>,
-    mut player: Query<(&mut Stats, &HurtBox), With<Player>>,
-    enemy: Query<(Entity, &HitBox), (With<Bat>, Without<PlayerBeenAttack>)>,
-) {
-    for (mut stats, hurtbox) in player.iter_mut() {
-        let hurtbox = hurtbox.owner.expect_safe();
-
-        for (bat_entity, hitbox) in enemy.iter() {
-            let hitbox = hitbox.owner.expect_safe();
-
-            if hurtbox.overlaps_area(hitbox) {
-                stats.health -= 1;
-
-                event.send(ChangeHealth {
-                    health: stats.health,
-                });
-
-                commands.entity(bat_entity).insert(PlayerBeenAttack);
-            }
-        }
-    }
-}
-
-pub fn attack_player_exit_system(
-    mut commands: Commands,
-    player: Query<&HurtBox, With<Player>>,
-    enemy: Query<(Entity, &HitBox), (With<Bat>, With<PlayerBeenAttack>)>,
+    mut damaged: EventWriter<Damaged>,
+    mut impulse: EventWriter<Impulse>,
+    mut rng: ResMut<CombatRng>,
+    tuning: Res<Tuning>,
+    mut player: Query<(&mut Stats, &mut PlayerFsm, &PlayerState, &Defense, &Player)>,
+    enemy: Query<&HitBox, With<Bat>>,
 ) {
-    for hurtbox in player.iter() {
-        let hurtbox = hurtbox.owner.expect_safe();
-
-        for (entity, hitbox) in enemy.iter() {
-            let hitbox = hitbox.owner.expect_safe();
-
-            if !hurtbox.overlaps_area(hitbox) {
-                commands.entity(entity).remove::<PlayerBeenAttack>();
+    for AreaEntered { a, b } in area_entered.iter() {
+        if let (Ok(hitbox), Ok((mut stats, mut fsm, state, defense, player))) =
+            (enemy.get(*a), player.get_mut(*b))
+        {
+            if *state == PlayerState::DEAD {
+                continue;
             }
-        }
-    }
-}
-
-pub fn player_no_health_system(
-    mut commands: Commands,
-    // mut game_over: ResMut<Option<GameOver>>,
-    player: Query<(Entity, &mut Stats, &Player)>,
-) {
-    for (entity, stats, player) in player.iter() {
-        let player = player.owner.expect_safe();
-        if stats.health == 0 {
-            commands.entity(entity).despawn();
-            player.queue_free();
-
-            // *game_over = Some(GameOver::Lose);
+            let position = player
+                .owner
+                .try_safe()
+                .map_or(Vector2::ZERO, |player| player.global_position());
+            let hit = formula::resolve(&hitbox.into(), Some(defense), &mut rng.0);
+            stats.health -= hit.amount;
+            fsm.request(*state, PlayerState::HURT);
+
+            damaged.send(Damaged {
+                target: *b,
+                source: *a,
+                amount: hit.amount,
+                kind: hitbox.kind,
+                crit: hit.crit,
+                position,
+            });
+            impulse.send(Impulse {
+                target: *b,
+                vector: hitbox.knockback_direction(position) * tuning.combat.knockback,
+            });
+            event.send(ChangeHealth {
+                health: stats.health,
+            });
         }
     }
 }
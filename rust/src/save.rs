@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::{App, EventReader, ParallelSystemDescriptorCoercion, Plugin, Res, ResMut};
+use gdnative::api::File;
+use gdnative::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bridge::lifecycle::{scene_tree, SafeRefExt};
+
+/// Path of the save file.
+const SAVE_PATH: &str = "user://save.ron";
+
+/// Progress kept across scene reloads and restarts.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct SaveData {
+    /// Names of the activated checkpoints, by scene path.
+    pub checkpoints: HashMap<String, HashSet<String>>,
+    /// Scene path and name of the last activated checkpoint.
+    pub last_checkpoint: Option<(String, String)>,
+    /// Flags set by dialogue.
+    pub flags: HashSet<String>,
+}
+
+impl SaveData {
+    fn load(path: &str) -> Option<Self> {
+        let file = File::new();
+        if !file.file_exists(path) {
+            return None;
+        }
+        if let Err(err) = file.open(path, File::READ) {
+            godot_error!("Could not open {}: {:?}", path, err);
+            return None;
+        }
+        let text = file.get_as_text().to_string();
+        file.close();
+
+        match ron::from_str(&text) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                godot_error!("Could not parse {}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    fn store(&self, path: &str) {
+        let text = match ron::ser::to_string_pretty(self, Default::default()) {
+            Ok(text) => text,
+            Err(err) => {
+                godot_error!("Could not serialize the save: {}", err);
+                return;
+            }
+        };
+
+        let file = File::new();
+        if let Err(err) = file.open(path, File::WRITE) {
+            godot_error!("Could not open {}: {:?}", path, err);
+            return;
+        }
+        file.store_string(text);
+        file.close();
+    }
+
+    pub fn is_activated(&self, scene: &str, checkpoint: &str) -> bool {
+        self.checkpoints
+            .get(scene)
+            .map_or(false, |checkpoints| checkpoints.contains(checkpoint))
+    }
+
+    pub fn is_last(&self, scene: &str, checkpoint: &str) -> bool {
+        self.last_checkpoint
+            .as_ref()
+            .map_or(false, |(s, c)| s == scene && c == checkpoint)
+    }
+
+    /// Record `checkpoint` of `scene` as activated and last.
+    pub fn activate(&mut self, scene: &str, checkpoint: &str) {
+        self.checkpoints
+            .entry(scene.to_string())
+            .or_default()
+            .insert(checkpoint.to_string());
+        self.last_checkpoint = Some((scene.to_string(), checkpoint.to_string()));
+    }
+}
+
+/// Save Resource.
+/// Loaded from `save.ron` on the first frame.
+#[derive(Default)]
+pub struct Save {
+    pub data: SaveData,
+    loaded: bool,
+}
+
+/// Asks to write the save file.
+pub struct Autosave;
+
+/// Path of the current scene, which keys saved progress.
+pub fn current_scene_path() -> Option<String> {
+    let scene = scene_tree()?.current_scene()?;
+    Some(scene.try_safe()?.filename().to_string())
+}
+
+/// Save Load System.
+pub fn save_load_system(mut save: ResMut<Save>) {
+    if save.loaded {
+        return;
+    }
+    save.loaded = true;
+
+    if let Some(data) = SaveData::load(SAVE_PATH) {
+        save.data = data;
+    }
+}
+
+/// Autosave System.
+/// Writes the save once per frame however many autosaves were asked for.
+pub fn autosave_system(mut autosave: EventReader<Autosave>, save: Res<Save>) {
+    if autosave.iter().count() > 0 {
+        save.data.store(SAVE_PATH);
+    }
+}
+
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Save>()
+            .add_event::<Autosave>()
+            .add_system(save_load_system.label("save_load"))
+            .add_system(autosave_system.after("save_load"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::{
+    App, Component, Entity, EventReader, EventWriter, ParallelSystemDescriptorCoercion, Plugin,
+    Query, Res, ResMut, Time, With,
+};
+
+use crate::combat::Damaged;
+use crate::components::Stats;
+use crate::tuning::Tuning;
+
+/// Time Scale Resource.
+/// Global slow motion, plus hit-stop freezes of the whole game or of
+/// single entities, counted down in real time.
+pub struct TimeScale {
+    scale: f32,
+    slow_motion: f32,
+    freeze: f32,
+    frozen: HashMap<Entity, f32>,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            slow_motion: 0.,
+            freeze: 0.,
+            frozen: HashMap::new(),
+        }
+    }
+}
+
+impl TimeScale {
+    /// The scale applied to `entity`.
+    /// Negative and non-finite scales stop time rather than running it
+    /// backwards.
+    pub fn factor(&self, entity: Entity, local: Option<&LocalTimeScale>) -> f32 {
+        if self.freeze > 0. || self.frozen.contains_key(&entity) {
+            return 0.;
+        }
+        let factor = self.scale * local.map_or(1., |local| local.0);
+        if factor.is_finite() {
+            factor.max(0.)
+        } else {
+            0.
+        }
+    }
+
+    /// Scale a delta in seconds.
+    pub fn delta(&self, delta: f32, entity: Entity, local: Option<&LocalTimeScale>) -> f32 {
+        delta * self.factor(entity, local)
+    }
+
+    /// Scale a frame duration, for ticking timers.
+    pub fn duration(
+        &self,
+        delta: Duration,
+        entity: Entity,
+        local: Option<&LocalTimeScale>,
+    ) -> Duration {
+        delta.mul_f32(self.factor(entity, local))
+    }
+}
+
+/// Local Time Scale Component.
+/// Scales time for a single entity on top of the global scale.
+#[derive(Component, Clone, Copy)]
+pub struct LocalTimeScale(pub f32);
+
+impl Default for LocalTimeScale {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Scales time globally by `scale` for `duration` real seconds, replacing
+/// any slow motion already running.
+pub struct SlowMotion {
+    pub scale: f32,
+    pub duration: f32,
+}
+
+/// Freezes `entities` for `duration` real seconds, or the whole game if
+/// `entities` is empty.
+pub struct HitStop {
+    pub entities: Vec<Entity>,
+    pub duration: f32,
+}
+
+/// Hit Stop System.
+/// Freezes attacker and victim briefly when a hit lands on a creature.
+pub fn hit_stop_system(
+    tuning: Res<Tuning>,
+    mut damaged: EventReader<Damaged>,
+    mut hit_stop: EventWriter<HitStop>,
+    creature: Query<(), With<Stats>>,
+) {
+    if tuning.combat.hit_stop <= 0. {
+        return;
+    }
+
+    for Damaged { target, source, .. } in damaged.iter() {
+        if !creature.contains(*target) {
+            continue;
+        }
+        hit_stop.send(HitStop {
+            entities: vec![*target, *source],
+            duration: tuning.combat.hit_stop,
+        });
+    }
+}
+
+/// Time Scale System.
+/// Counts down slow motion and freezes in real time and starts new ones.
+pub fn time_scale_system(
+    time: Res<Time>,
+    mut time_scale: ResMut<TimeScale>,
+    mut slow_motion: EventReader<SlowMotion>,
+    mut hit_stop: EventReader<HitStop>,
+) {
+    let delta = time.delta_seconds();
+    if time_scale.slow_motion > 0. {
+        time_scale.slow_motion -= delta;
+        if time_scale.slow_motion <= 0. {
+            time_scale.scale = 1.;
+        }
+    }
+    time_scale.freeze = (time_scale.freeze - delta).max(0.);
+    time_scale.frozen.retain(|_, freeze| {
+        *freeze -= delta;
+        *freeze > 0.
+    });
+
+    for SlowMotion { scale, duration } in slow_motion.iter() {
+        if *duration > 0. {
+            time_scale.scale = *scale;
+            time_scale.slow_motion = *duration;
+        }
+    }
+    for HitStop { entities, duration } in hit_stop.iter() {
+        if entities.is_empty() {
+            time_scale.freeze = time_scale.freeze.max(*duration);
+        }
+        for entity in entities {
+            let freeze = time_scale.frozen.entry(*entity).or_default();
+            *freeze = freeze.max(*duration);
+        }
+    }
+}
+
+pub struct TimeScalePlugin;
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeScale>()
+            .add_event::<SlowMotion>()
+            .add_event::<HitStop>()
+            .add_system(hit_stop_system.label("hit_stop"))
+            .add_system(time_scale_system.after("hit_stop"));
+    }
+}
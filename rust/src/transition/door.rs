@@ -0,0 +1,31 @@
+use bevy::prelude::Component;
+use gdnative::api::Area2D;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::connect_area_signals;
+
+/// Door.
+/// Sends the player to `spawn_point` in `target_scene` when they walk in.
+#[gdrust(extends = Area2D)]
+#[derive(Component, Clone)]
+pub struct Door {
+    #[export]
+    pub target_scene: String,
+    #[export]
+    pub spawn_point: String,
+}
+
+#[methods]
+impl Door {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Area2D>) {
+        connect_area_signals(owner);
+
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+}
@@ -0,0 +1,32 @@
+use gdnative::api::ColorRect;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::lifecycle::SafeRefExt;
+
+/// Fade Rect Resource.
+/// The screen fade drawn over every scene.
+pub struct FadeRect(pub Ref<ColorRect>);
+
+impl FadeRect {
+    pub fn set_alpha(&self, alpha: f32) {
+        if let Some(rect) = self.0.try_safe() {
+            rect.set_modulate(Color::from_rgba(1., 1., 1., alpha));
+        }
+    }
+}
+
+/// Screen Fade.
+/// Autoloaded, so it outlives the scenes it fades between.
+#[gdrust(extends = ColorRect)]
+#[derive(Default, Clone)]
+pub struct ScreenFade;
+
+#[methods]
+impl ScreenFade {
+    #[export]
+    fn _ready(&mut self, owner: TRef<ColorRect>) {
+        with_world(|w| w.insert_resource(FadeRect(owner.claim())));
+    }
+}
@@ -0,0 +1,243 @@
+use bevy::prelude::{
+    Added, App, Commands, Component, DespawnRecursiveExt, Entity, EventReader, EventWriter,
+    ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, Time, Timer, With, Without,
+};
+use gdnative::prelude::*;
+use gdrust::ecs::engine_sync::components::GameNode;
+
+use crate::bridge::lifecycle::{scene_tree, SafeRefExt};
+use crate::bridge::registry::NodeRegistry;
+use crate::bridge::BodyEntered;
+use crate::components::Stats;
+use crate::player::{Player, RespawnPoint};
+use crate::transition::door::Door;
+use crate::transition::fade::FadeRect;
+use crate::transition::spawn_point::SpawnPoint;
+use crate::world::health::{ChangeHealth, ChangeMaxHealth};
+
+pub mod door;
+pub mod fade;
+pub mod spawn_point;
+
+/// Seconds taken by each of the fade out and the fade in.
+const FADE_DURATION: f32 = 0.3;
+
+/// Asks to load `scene` and place the player at its `spawn_point`.
+#[derive(Clone)]
+pub struct TransitionRequest {
+    pub scene: String,
+    pub spawn_point: String,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum TransitionPhase {
+    Idle,
+    FadeOut,
+    Loading,
+    FadeIn,
+}
+
+/// Travelling Component.
+/// Marks the player entity between leaving its scene and its node in the
+/// next scene attaching to it.
+#[derive(Component, Clone, Copy)]
+pub struct Travelling;
+
+/// Transition Resource.
+/// The transition in progress, if any.
+pub struct Transition {
+    phase: TransitionPhase,
+    request: Option<TransitionRequest>,
+    timer: Timer,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            phase: TransitionPhase::Idle,
+            request: None,
+            timer: Timer::from_seconds(FADE_DURATION, false),
+        }
+    }
+}
+
+impl Transition {
+    fn enter(&mut self, phase: TransitionPhase) {
+        self.phase = phase;
+        self.timer.reset();
+    }
+}
+
+/// Door System.
+/// Requests a transition when the player walks into a door.
+pub fn door_system(
+    mut body_entered: EventReader<BodyEntered>,
+    mut request: EventWriter<TransitionRequest>,
+    door: Query<&Door>,
+    player: Query<(), With<Player>>,
+) {
+    for BodyEntered { a, b } in body_entered.iter() {
+        if let (Ok(door), true) = (door.get(*a), player.contains(*b)) {
+            request.send(TransitionRequest {
+                scene: door.target_scene.clone(),
+                spawn_point: door.spawn_point.clone(),
+            });
+        }
+    }
+}
+
+/// Transition Request System.
+/// Starts fading out.
+pub fn transition_request_system(
+    mut requests: EventReader<TransitionRequest>,
+    mut transition: ResMut<Transition>,
+) {
+    for request in requests.iter() {
+        if transition.phase != TransitionPhase::Idle {
+            continue;
+        }
+        if request.scene.is_empty() {
+            godot_warn!(
+                "Transition to spawn point `{}` has no scene",
+                request.spawn_point
+            );
+            continue;
+        }
+
+        transition.request = Some(request.clone());
+        transition.enter(TransitionPhase::FadeOut);
+    }
+}
+
+/// Transition System.
+/// Drives the fade and swaps the scene once the screen is black. The player
+/// entity is kept for the player node of the next scene.
+pub fn transition_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    fade: Option<Res<FadeRect>>,
+    mut transition: ResMut<Transition>,
+    mut registry: ResMut<NodeRegistry>,
+    game: Query<Entity, With<GameNode>>,
+    player: Query<Entity, With<Player>>,
+) {
+    match transition.phase {
+        TransitionPhase::Idle | TransitionPhase::Loading => return,
+        TransitionPhase::FadeOut | TransitionPhase::FadeIn => {
+            transition.timer.tick(time.delta());
+        }
+    }
+
+    let alpha = match transition.phase {
+        TransitionPhase::FadeOut => transition.timer.percent(),
+        _ => transition.timer.percent_left(),
+    };
+    if let Some(fade) = fade {
+        fade.set_alpha(alpha);
+    }
+
+    if !transition.timer.finished() {
+        return;
+    }
+
+    if transition.phase == TransitionPhase::FadeIn {
+        transition.request = None;
+        transition.enter(TransitionPhase::Idle);
+        return;
+    }
+
+    let scene = match &transition.request {
+        Some(request) => request.scene.clone(),
+        None => {
+            transition.enter(TransitionPhase::FadeIn);
+            return;
+        }
+    };
+    let changed = scene_tree().map(|tree| tree.change_scene(scene.as_str()));
+    if let Some(Err(err)) = changed {
+        godot_error!("Could not change scene to {}: {:?}", scene, err);
+        transition.enter(TransitionPhase::FadeIn);
+        return;
+    }
+
+    // the old scene is freed by Godot at the end of the frame, forgetting
+    // the player's nodes keeps that from despawning the player entity
+    for entity in player.iter() {
+        registry.remove_entity(entity);
+        commands.entity(entity).insert(Travelling);
+    }
+    for entity in game.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    transition.enter(TransitionPhase::Loading);
+}
+
+/// Arrival System.
+/// Places the player at the spawn point once the new scene is in the ECS.
+pub fn arrival_system(
+    mut commands: Commands,
+    mut transition: ResMut<Transition>,
+    game: Query<(), Added<GameNode>>,
+    mut player: Query<(&Player, &Stats, &mut RespawnPoint), Without<Travelling>>,
+    travelling: Query<Entity, With<Travelling>>,
+    spawn_points: Query<&SpawnPoint>,
+    mut health_event: EventWriter<ChangeHealth>,
+    mut max_health_event: EventWriter<ChangeMaxHealth>,
+) {
+    if transition.phase != TransitionPhase::Loading || game.is_empty() {
+        return;
+    }
+
+    let spawn_point = transition
+        .request
+        .as_ref()
+        .map(|request| request.spawn_point.clone())
+        .unwrap_or_default();
+    let position = spawn_points
+        .iter()
+        .filter_map(|point| point.owner.try_safe())
+        .find(|point| point.name().to_string() == spawn_point)
+        .map(|point| point.global_position());
+    if position.is_none() && !spawn_point.is_empty() {
+        godot_warn!("Spawn point `{}` not found", spawn_point);
+    }
+
+    // no player node in the new scene attached to it
+    for entity in travelling.iter() {
+        godot_warn!("No player in the new scene");
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (player, stats, mut respawn) in player.iter_mut() {
+        // the health UI of the new scene starts from its defaults
+        max_health_event.send(ChangeMaxHealth {
+            max_health: stats.max_health,
+        });
+        health_event.send(ChangeHealth {
+            health: stats.health,
+        });
+
+        if let (Some(position), Some(player)) = (position, player.owner.try_safe()) {
+            player.set_global_position(position);
+            respawn.0 = position;
+        }
+    }
+
+    transition.enter(TransitionPhase::FadeIn);
+}
+
+pub struct TransitionPlugin;
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transition>()
+            .add_event::<TransitionRequest>()
+            .add_system(door_system.label("door").after("node_signal"))
+            .add_system(
+                transition_request_system
+                    .label("transition_request")
+                    .after("door"),
+            )
+            .add_system(transition_system.after("transition_request"))
+            .add_system(arrival_system.label("arrival").after("tuning_apply"));
+    }
+}
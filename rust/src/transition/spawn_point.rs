@@ -0,0 +1,25 @@
+use bevy::prelude::Component;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::builder::GodotEntity;
+
+/// Spawn Point.
+/// Where the player arrives in a scene, looked up by node name.
+#[gdrust(extends = Position2D)]
+#[derive(Component, Clone)]
+pub struct SpawnPoint {
+    #[default(_owner.claim())]
+    pub owner: Ref<Position2D>,
+}
+
+#[methods]
+impl SpawnPoint {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Position2D>) {
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{
+    App, ChangeTrackers, Component, EventWriter, ParallelSystemDescriptorCoercion, Plugin, Query,
+    Res, ResMut, Time, Timer,
+};
+use gdnative::api::File;
+use gdnative::export::Export;
+use gdnative::prelude::*;
+use serde::Deserialize;
+
+use crate::components::{Acceleration, Defense, Friction, Knockback, Roll, Stats};
+use crate::delect_box::hit_box::HitBox;
+use crate::enemy::wander_controller::WanderController;
+use crate::inspector::{clamp_field, FieldValue, Inspect};
+use crate::player::Player;
+use crate::world::health::{ChangeHealth, ChangeMaxHealth};
+
+/// Path of the gameplay tuning file.
+const TUNING_PATH: &str = "res://tuning.ron";
+
+/// Combat Tuning.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct CombatTuning {
+    /// Seconds attacker and victim freeze when a hit lands.
+    pub hit_stop: f32,
+    /// Impulse of a hit on a body of mass 1.
+    pub knockback: f32,
+    /// Seeds crits and damage variance, random if unset.
+    pub seed: Option<u64>,
+}
+
+impl Default for CombatTuning {
+    fn default() -> Self {
+        Self {
+            hit_stop: 0.05,
+            knockback: 120.,
+            seed: None,
+        }
+    }
+}
+
+/// Death Tuning.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct DeathTuning {
+    /// Seconds between dying and respawning.
+    pub respawn_delay: f32,
+    /// Fraction of max health restored on respawn, at least one point.
+    pub health: f32,
+    /// Fraction of every item stack lost on death, rounded down.
+    pub item_loss: f32,
+}
+
+impl Default for DeathTuning {
+    fn default() -> Self {
+        Self {
+            respawn_delay: 1.,
+            health: 1.,
+            item_loss: 0.,
+        }
+    }
+}
+
+/// A named set of component values.
+/// Fields left out keep the value set in the inspector.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Preset {
+    pub max_health: Option<i32>,
+    pub max_speed: Option<f32>,
+    pub acceleration: Option<f32>,
+    pub friction: Option<f32>,
+    pub roll_speed: Option<f32>,
+    pub roll_cooldown: Option<f32>,
+    pub damage: Option<i32>,
+    pub crit_chance: Option<f32>,
+    pub crit_multiplier: Option<f32>,
+    pub variance: Option<f32>,
+    pub armor: Option<i32>,
+    pub mass: Option<f32>,
+    pub knockback_decay: Option<f32>,
+    pub wander_range: Option<f32>,
+}
+
+/// One possible drop of a loot table.
+#[derive(Deserialize, Clone)]
+pub struct LootDrop {
+    pub item: String,
+    pub chance: f64,
+    #[serde(default = "one")]
+    pub count: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// Tuning Resource.
+/// Gameplay values loaded from `tuning.ron`.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Tuning {
+    pub combat: CombatTuning,
+    pub death: DeathTuning,
+    pub presets: HashMap<String, Preset>,
+    pub loot: HashMap<String, Vec<LootDrop>>,
+}
+
+impl Tuning {
+    fn load(path: &str) -> Option<Self> {
+        let file = File::new();
+        if let Err(err) = file.open(path, File::READ) {
+            godot_error!("Could not open {}: {:?}", path, err);
+            return None;
+        }
+        let text = file.get_as_text().to_string();
+        file.close();
+
+        match ron::from_str(&text) {
+            Ok(tuning) => Some(tuning),
+            Err(err) => {
+                godot_error!("Could not parse {}: {}", path, err);
+                None
+            }
+        }
+    }
+}
+
+/// Tuning Preset Component.
+/// Name of the preset in `tuning.ron` applied to this entity.
+#[derive(Component, Default, Clone)]
+pub struct TuningPreset(pub String);
+impl ToVariant for TuningPreset {
+    fn to_variant(&self) -> Variant {
+        self.0.to_variant()
+    }
+}
+impl FromVariant for TuningPreset {
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        String::from_variant(variant).map(TuningPreset)
+    }
+}
+impl Export for TuningPreset {
+    type Hint = ();
+    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
+        ExportInfo::new(VariantType::GodotString)
+    }
+}
+
+/// Tuning Watcher Resource.
+/// Polls the modification time of the tuning file.
+pub struct TuningWatcher {
+    modified: Option<i64>,
+    timer: Timer,
+}
+
+impl Default for TuningWatcher {
+    fn default() -> Self {
+        Self {
+            modified: None,
+            timer: Timer::from_seconds(1., true),
+        }
+    }
+}
+
+/// Tuning Watch System.
+/// Reloads the tuning file when it changes on disk.
+pub fn tuning_watch_system(
+    time: Res<Time>,
+    mut watcher: ResMut<TuningWatcher>,
+    mut tuning: ResMut<Tuning>,
+) {
+    watcher.timer.tick(time.delta());
+    if watcher.modified.is_some() && !watcher.timer.just_finished() {
+        return;
+    }
+
+    let modified = File::new().get_modified_time(TUNING_PATH);
+    if watcher.modified == Some(modified) {
+        return;
+    }
+    watcher.modified = Some(modified);
+
+    if let Some(loaded) = Tuning::load(TUNING_PATH) {
+        *tuning = loaded;
+    }
+}
+
+/// Set an inspected field from a preset, clamped to its inspector range.
+fn tune<T: Inspect>(component: &mut T, owner: &str, field: &str, value: Option<impl ToVariant>) {
+    if let Some(value) = value {
+        component.set_field(field, &value.to_variant(), owner);
+    }
+}
+
+/// Set a field that is not inspected from a preset, clamped to `min..=max`.
+fn tune_value<T: FieldValue>(
+    field: &mut T,
+    owner: &str,
+    name: &str,
+    value: Option<T>,
+    min: f64,
+    max: f64,
+) {
+    if let Some(value) = value {
+        *field = clamp_field(owner, name, &value.to_variant(), *field, min, max);
+    }
+}
+
+/// Apply Tuning System.
+/// Applies presets to new entities, and to every entity after a reload.
+/// Values outside a field's range are clamped with a warning.
+pub fn apply_tuning_system(
+    tuning: Res<Tuning>,
+    mut health_event: EventWriter<ChangeHealth>,
+    mut max_health_event: EventWriter<ChangeMaxHealth>,
+    mut query: Query<(
+        &TuningPreset,
+        ChangeTrackers<TuningPreset>,
+        Option<&Player>,
+        Option<&mut Stats>,
+        Option<&mut Acceleration>,
+        Option<&mut Friction>,
+        Option<&mut Roll>,
+        Option<&mut HitBox>,
+        Option<&mut Defense>,
+        Option<&mut Knockback>,
+        Option<&mut WanderController>,
+    )>,
+) {
+    for (
+        preset,
+        tracker,
+        player,
+        stats,
+        acceleration,
+        friction,
+        roll,
+        hitbox,
+        defense,
+        knockback,
+        wander_controller,
+    ) in query.iter_mut()
+    {
+        if !tuning.is_changed() && !tracker.is_added() {
+            continue;
+        }
+
+        if preset.0.is_empty() {
+            continue;
+        }
+        let owner = format!("{} preset `{}`", TUNING_PATH, preset.0);
+        let preset = match tuning.presets.get(&preset.0) {
+            Some(preset) => preset,
+            None => {
+                godot_warn!("Unknown tuning preset `{}`", preset.0);
+                continue;
+            }
+        };
+
+        if let (Some(mut stats), Some(max_health)) = (stats, preset.max_health) {
+            tune(&mut *stats, &owner, "max_health", Some(max_health));
+            let max_health = stats.max_health;
+            // fresh entities start at full health, live ones keep their damage
+            if tracker.is_added() {
+                stats.health = max_health;
+            }
+            stats.health = stats.health.min(max_health);
+
+            if player.is_some() {
+                max_health_event.send(ChangeMaxHealth { max_health });
+                health_event.send(ChangeHealth {
+                    health: stats.health,
+                });
+            }
+        }
+        if let Some(mut acceleration) = acceleration {
+            tune(&mut *acceleration, &owner, "max_speed", preset.max_speed);
+            tune(
+                &mut *acceleration,
+                &owner,
+                "acceleration",
+                preset.acceleration,
+            );
+        }
+        if let Some(mut friction) = friction {
+            tune(&mut *friction, &owner, "friction", preset.friction);
+        }
+        if let Some(mut roll) = roll {
+            tune(&mut *roll, &owner, "roll_speed", preset.roll_speed);
+            tune(&mut *roll, &owner, "cooldown", preset.roll_cooldown);
+        }
+        if let Some(mut hitbox) = hitbox {
+            let hitbox = &mut *hitbox;
+            tune_value(&mut hitbox.damage, &owner, "damage", preset.damage, 0., 99.);
+            tune_value(
+                &mut hitbox.crit_chance,
+                &owner,
+                "crit_chance",
+                preset.crit_chance,
+                0.,
+                1.,
+            );
+            tune_value(
+                &mut hitbox.crit_multiplier,
+                &owner,
+                "crit_multiplier",
+                preset.crit_multiplier,
+                1.,
+                10.,
+            );
+            tune_value(
+                &mut hitbox.variance,
+                &owner,
+                "variance",
+                preset.variance,
+                0.,
+                1.,
+            );
+        }
+        if let Some(mut defense) = defense {
+            tune(&mut *defense, &owner, "armor", preset.armor);
+        }
+        if let Some(mut knockback) = knockback {
+            tune(&mut *knockback, &owner, "mass", preset.mass);
+            tune(&mut *knockback, &owner, "decay", preset.knockback_decay);
+        }
+        if let Some(mut wander_controller) = wander_controller {
+            tune_value(
+                &mut wander_controller.wander_range,
+                &owner,
+                "wander_range",
+                preset.wander_range,
+                0.,
+                1000.,
+            );
+        }
+    }
+}
+
+pub struct TuningPlugin;
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tuning>()
+            .init_resource::<TuningWatcher>()
+            .add_system(tuning_watch_system.label("tuning_watch"))
+            .add_system(
+                apply_tuning_system
+                    .label("tuning_apply")
+                    .after("tuning_watch"),
+            );
+    }
+}
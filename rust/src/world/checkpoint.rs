@@ -0,0 +1,136 @@
+use bevy::prelude::{Added, Component, EventReader, EventWriter, Query, Res, ResMut, With};
+use gdnative::api::Area2D;
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::{connect_area_signals, BodyEntered};
+use crate::components::Stats;
+use crate::player::{Player, RespawnPoint};
+use crate::save::{current_scene_path, Autosave, Save};
+use crate::world::health::ChangeHealth;
+
+/// Checkpoint.
+/// Becomes the player's respawn point when they walk in, keyed in the save
+/// by node name and scene.
+#[gdrust(extends = Area2D)]
+#[derive(Component, Clone)]
+pub struct Checkpoint {
+    #[default(_owner.claim())]
+    pub owner: Ref<Area2D>,
+    /// Restore the player's health when it becomes the respawn point.
+    #[export]
+    #[default(true)]
+    pub heal: bool,
+    /// Write the save when it becomes the respawn point.
+    #[export]
+    #[default(true)]
+    pub autosave: bool,
+    pub active: bool,
+}
+
+#[methods]
+impl Checkpoint {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Area2D>) {
+        connect_area_signals(owner);
+        self.show_active(false);
+
+        with_world(|w| {
+            GodotEntity::spawn(w, owner).insert(self.clone());
+        });
+    }
+}
+
+impl Checkpoint {
+    /// Inactive checkpoints are drawn dimmed.
+    fn show_active(&self, active: bool) {
+        if let Some(owner) = self.owner.try_safe() {
+            let shade = if active { 1. } else { 0.5 };
+            owner.set_modulate(Color::from_rgb(shade, shade, shade));
+        }
+    }
+
+    fn node_name(&self) -> Option<String> {
+        Some(self.owner.try_safe()?.name().to_string())
+    }
+}
+
+/// Checkpoint Restore System.
+/// Restores activation from the save when a scene loads, and respawns the
+/// player at the last checkpoint if it is in this scene.
+pub fn checkpoint_restore_system(
+    save: Res<Save>,
+    mut checkpoint: Query<&mut Checkpoint, Added<Checkpoint>>,
+    mut player: Query<&mut RespawnPoint, With<Player>>,
+) {
+    let scene = match current_scene_path() {
+        Some(scene) => scene,
+        None => return,
+    };
+
+    for mut checkpoint in checkpoint.iter_mut() {
+        let (name, owner) = match (checkpoint.node_name(), checkpoint.owner.try_safe()) {
+            (Some(name), Some(owner)) => (name, owner),
+            _ => continue,
+        };
+
+        checkpoint.active = save.data.is_activated(&scene, &name);
+        checkpoint.show_active(checkpoint.active);
+
+        if save.data.is_last(&scene, &name) {
+            for mut respawn in player.iter_mut() {
+                respawn.0 = owner.global_position();
+            }
+        }
+    }
+}
+
+/// Checkpoint System.
+/// Activates checkpoints the player walks into.
+pub fn checkpoint_system(
+    mut body_entered: EventReader<BodyEntered>,
+    mut save: ResMut<Save>,
+    mut autosave: EventWriter<Autosave>,
+    mut health_event: EventWriter<ChangeHealth>,
+    mut checkpoint: Query<&mut Checkpoint>,
+    mut player: Query<(&mut RespawnPoint, &mut Stats), With<Player>>,
+) {
+    for BodyEntered { a, b } in body_entered.iter() {
+        let (mut checkpoint, (mut respawn, mut stats)) =
+            match (checkpoint.get_mut(*a), player.get_mut(*b)) {
+                (Ok(checkpoint), Ok(player)) => (checkpoint, player),
+                _ => continue,
+            };
+        let (scene, name, owner) = match (
+            current_scene_path(),
+            checkpoint.node_name(),
+            checkpoint.owner.try_safe(),
+        ) {
+            (Some(scene), Some(name), Some(owner)) => (scene, name, owner),
+            _ => continue,
+        };
+
+        respawn.0 = owner.global_position();
+        if save.data.is_last(&scene, &name) {
+            continue;
+        }
+
+        save.data.activate(&scene, &name);
+        if !checkpoint.active {
+            checkpoint.active = true;
+            checkpoint.show_active(true);
+        }
+        if checkpoint.heal {
+            stats.health = stats.max_health;
+            health_event.send(ChangeHealth {
+                health: stats.health,
+            });
+        }
+        if checkpoint.autosave {
+            autosave.send(Autosave);
+        }
+    }
+}
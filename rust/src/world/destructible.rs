@@ -0,0 +1,186 @@
+use bevy::prelude::{
+    Commands, Component, Entity, EventReader, EventWriter, Query, Res, ResMut, Time, Timer, Without,
+};
+use gdnative::prelude::*;
+use gdrust::ecs::app::with_world;
+use gdrust::macros::*;
+use gdrust::unsafe_functions::{NodeTreeExt, ResourceLoaderExt};
+use rand::Rng;
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::bridge::AreaEntered;
+use crate::combat::{formula, CombatRng, Damaged};
+use crate::components::{DamageKind, Inventory};
+use crate::delect_box::hit_box::{HitBox, Swing};
+use crate::delect_box::hurt_box::HurtBox;
+use crate::effect::add_effect;
+use crate::time_scale::{LocalTimeScale, TimeScale};
+use crate::tuning::Tuning;
+
+/// Destructible Component.
+/// Grass, bushes, pots and crates: breaks once its health runs out, drops
+/// loot into the attacker's inventory and optionally grows back.
+#[gdrust(extends = Node2D)]
+#[derive(Component, Clone)]
+pub struct Destructible {
+    #[default(_owner.claim())]
+    pub owner: Ref<Node2D>,
+    #[export]
+    #[default(1)]
+    pub max_health: i32,
+    pub health: i32,
+    #[export]
+    pub required_damage: DamageKind,
+    #[export]
+    #[default("res://scenes/effect/GrassEffect.tscn".to_string())]
+    pub break_effect: String,
+    pub effect: Option<Ref<PackedScene>>,
+    /// Loot table in `tuning.ron`.
+    #[export]
+    pub loot_table: String,
+    /// Seconds until it grows back, never if zero.
+    #[export]
+    pub regrow_time: f32,
+}
+
+#[methods]
+impl Destructible {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Node2D>) {
+        self.health = self.max_health;
+        if !self.break_effect.is_empty() {
+            self.effect = Some(
+                ResourceLoader::godot_singleton().expect_load_scene(self.break_effect.as_str()),
+            );
+        }
+
+        with_world(|w| {
+            GodotEntity::spawn(w, owner)
+                .insert(self.clone())
+                .child::<HurtBox>("HurtBox");
+        })
+    }
+}
+
+/// Regrow Component.
+/// Counts down while a broken destructible is hidden.
+#[derive(Component)]
+pub struct Regrow(pub Timer);
+
+/// Destructible System.
+/// Damages destructibles hit by a hitbox of the required damage kind.
+pub fn destructible_system(
+    mut commands: Commands,
+    mut area_entered: EventReader<AreaEntered>,
+    mut damaged: EventWriter<Damaged>,
+    tuning: Res<Tuning>,
+    mut rng: ResMut<CombatRng>,
+    hitbox: Query<&HitBox>,
+    mut swing: Query<&mut Swing>,
+    mut inventory: Query<&mut Inventory>,
+    mut destructible: Query<(&mut Destructible, Option<&HurtBox>), Without<Regrow>>,
+) {
+    for AreaEntered { a, b } in area_entered.iter() {
+        let (hitbox, (mut destructible, hurtbox)) = match (hitbox.get(*a), destructible.get_mut(*b))
+        {
+            (Ok(hitbox), Ok(destructible)) => (hitbox, destructible),
+            _ => continue,
+        };
+
+        if destructible.health <= 0 || !hitbox.kind.satisfies(destructible.required_damage) {
+            continue;
+        }
+        if let Ok(mut swing) = swing.get_mut(*a) {
+            if !swing.land(*b) {
+                continue;
+            }
+        }
+        let node = match destructible.owner.try_safe() {
+            Some(node) => node,
+            None => continue,
+        };
+
+        let hit = formula::resolve(&hitbox.into(), None, &mut rng.0);
+        destructible.health -= hit.amount;
+        damaged.send(Damaged {
+            target: *b,
+            source: *a,
+            amount: hit.amount,
+            kind: hitbox.kind,
+            crit: hit.crit,
+            position: node.global_position(),
+        });
+        if destructible.health > 0 {
+            continue;
+        }
+
+        // spawn the effect
+        if let (Some(effect), Some(parent)) = (
+            &destructible.effect,
+            node.expect_tree()
+                .current_scene()
+                .and_then(|scene| scene.try_safe()),
+        ) {
+            add_effect(&mut commands, effect, node.global_position(), parent);
+        }
+
+        // drop the loot
+        if let (Ok(mut inventory), Some(table)) = (
+            inventory.get_mut(*a),
+            tuning.loot.get(&destructible.loot_table),
+        ) {
+            for drop in table {
+                if rng.0.gen_bool(drop.chance.clamp(0., 1.)) {
+                    *inventory.items.entry(drop.item.clone()).or_default() += drop.count;
+                }
+            }
+        }
+
+        if destructible.regrow_time > 0. {
+            node.hide();
+            if let Some(hurtbox) = hurtbox.and_then(|hurtbox| hurtbox.owner.try_safe()) {
+                hurtbox.set_deferred("monitorable", false);
+            }
+            commands
+                .entity(*b)
+                .insert(Regrow(Timer::from_seconds(destructible.regrow_time, false)));
+        } else {
+            commands.entity(*b).despawn();
+            node.queue_free();
+        }
+    }
+}
+
+/// Regrow System.
+/// Restores broken destructibles once their regrow timer runs out.
+pub fn regrow_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut destructible: Query<(
+        Entity,
+        &mut Destructible,
+        &mut Regrow,
+        Option<&HurtBox>,
+        Option<&LocalTimeScale>,
+    )>,
+) {
+    for (entity, mut destructible, mut regrow, hurtbox, local) in destructible.iter_mut() {
+        regrow
+            .0
+            .tick(time_scale.duration(time.delta(), entity, local));
+        if !regrow.0.finished() {
+            continue;
+        }
+
+        destructible.health = destructible.max_health;
+        if let Some(node) = destructible.owner.try_safe() {
+            node.show();
+        }
+        if let Some(hurtbox) = hurtbox.and_then(|hurtbox| hurtbox.owner.try_safe()) {
+            hurtbox.set_deferred("monitorable", true);
+        }
+        commands.entity(entity).remove::<Regrow>();
+    }
+}
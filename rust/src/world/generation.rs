@@ -0,0 +1,486 @@
+use std::collections::VecDeque;
+
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Map cells per cliff cell along each axis.
+/// Cliff tiles are 32px while the map is laid out on the 16px dirt grid.
+pub const CLIFF_SCALE: usize = 2;
+
+/// Smallest distance kept between two bat spawners.
+const BAT_SPACING: usize = 4;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tile {
+    Grass,
+    Dirt,
+    Cliff,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Prop {
+    Grass,
+    Bush,
+    Tree,
+    BatSpawner,
+}
+
+impl Prop {
+    /// Whether the prop blocks the player.
+    pub fn is_solid(self) -> bool {
+        matches!(self, Prop::Bush | Prop::Tree)
+    }
+}
+
+/// Generation Settings.
+/// Densities are the chance of a prop on each free grass cell.
+#[derive(Clone)]
+pub struct Generation {
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub cliff_density: f64,
+    pub grass_density: f64,
+    pub bush_density: f64,
+    pub tree_density: f64,
+    pub bat_spawners: usize,
+    pub bat_distance: usize,
+}
+
+impl Default for Generation {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            width: 40,
+            height: 24,
+            cliff_density: 0.35,
+            grass_density: 0.08,
+            bush_density: 0.03,
+            tree_density: 0.02,
+            bat_spawners: 4,
+            bat_distance: 8,
+        }
+    }
+}
+
+/// A generated world, in 16px cells.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldMap {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<Tile>,
+    pub props: Vec<(usize, usize, Prop)>,
+    pub spawn: (usize, usize),
+}
+
+impl WorldMap {
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.tiles[y * self.width + x]
+    }
+
+    pub fn prop(&self, x: usize, y: usize) -> Option<Prop> {
+        self.props
+            .iter()
+            .find(|(px, py, _)| (*px, *py) == (x, y))
+            .map(|(_, _, prop)| *prop)
+    }
+
+    pub fn is_walkable(&self, x: usize, y: usize) -> bool {
+        self.tile(x, y) != Tile::Cliff && !self.prop(x, y).map_or(false, Prop::is_solid)
+    }
+
+    /// Cells that can be walked to from the spawn.
+    pub fn reachable(&self) -> Vec<bool> {
+        let blocked = Grid {
+            width: self.width,
+            height: self.height,
+            cells: (0..self.tiles.len())
+                .map(|i| !self.is_walkable(i % self.width, i / self.width))
+                .collect(),
+        };
+        blocked.flood(self.spawn)
+    }
+
+    /// Whether every walkable cell can be reached from the spawn.
+    pub fn is_connected(&self) -> bool {
+        let reached = self.reachable();
+        (0..self.tiles.len())
+            .all(|i| reached[i] || !self.is_walkable(i % self.width, i / self.width))
+    }
+}
+
+#[derive(Clone)]
+struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![false; width * height],
+        }
+    }
+
+    /// Out of bounds reads as set, so the map edge behaves like a wall.
+    fn get(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+            return true;
+        }
+        self.cells[y as usize * self.width + x as usize]
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.cells[y * self.width + x] = value;
+    }
+
+    fn neighbours(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) && self.get(x as isize + dx, y as isize + dy) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Whether the set cell at `x`, `y` is part of a fully set 2x2 block.
+    fn in_block(&self, x: usize, y: usize) -> bool {
+        let (x, y) = (x as isize, y as isize);
+        [(-1, -1), (0, -1), (-1, 0), (0, 0)]
+            .iter()
+            .any(|(ox, oy)| (0..2).all(|dy| (0..2).all(|dx| self.get(x + ox + dx, y + oy + dy))))
+    }
+
+    /// Cells reachable from `start` through unset cells.
+    fn flood(&self, start: (usize, usize)) -> Vec<bool> {
+        let mut reached = vec![false; self.cells.len()];
+        let mut queue = VecDeque::from([start]);
+        reached[start.1 * self.width + start.0] = true;
+
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if self.get(nx, ny) {
+                    continue;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                if !reached[ny * self.width + nx] {
+                    reached[ny * self.width + nx] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        reached
+    }
+}
+
+/// Generate a world from `settings`. The same settings always give the same map.
+pub fn generate(settings: &Generation) -> WorldMap {
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+
+    let cliffs = generate_cliffs(settings, &mut rng);
+    let (width, height) = (cliffs.width * CLIFF_SCALE, cliffs.height * CLIFF_SCALE);
+    let spawn = nearest_open(&cliffs);
+
+    let mut map = WorldMap {
+        width,
+        height,
+        tiles: (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width / CLIFF_SCALE, i / width / CLIFF_SCALE);
+                if cliffs.get(x as isize, y as isize) {
+                    Tile::Cliff
+                } else {
+                    Tile::Grass
+                }
+            })
+            .collect(),
+        props: Vec::new(),
+        spawn: (spawn.0 * CLIFF_SCALE, spawn.1 * CLIFF_SCALE),
+    };
+
+    place_bat_spawners(&mut map, settings, &mut rng);
+    place_props(&mut map, settings, &mut rng);
+    map
+}
+
+/// Cliffs on the coarse grid: noise smoothed into blobs, with every open area
+/// joined to the spawn and every cliff part of a 2x2 block so the autotile has
+/// an edge piece for it.
+fn generate_cliffs(settings: &Generation, rng: &mut StdRng) -> Grid {
+    let width = (settings.width / CLIFF_SCALE).max(4);
+    let height = (settings.height / CLIFF_SCALE).max(4);
+
+    let mut cliffs = Grid::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            cliffs.set(x, y, border || rng.gen_bool(settings.cliff_density));
+        }
+    }
+
+    for _ in 0..2 {
+        let previous = cliffs.clone();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                match previous.neighbours(x, y) {
+                    n if n >= 5 => cliffs.set(x, y, true),
+                    n if n <= 3 => cliffs.set(x, y, false),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let spawn = nearest_open(&cliffs);
+    cliffs.set(spawn.0, spawn.1, false);
+
+    // pruning and carving only ever remove cliffs, so this terminates
+    loop {
+        prune_cliffs(&mut cliffs);
+        let reached = cliffs.flood(spawn);
+        let unreached = (0..cliffs.cells.len()).find(|&i| !cliffs.cells[i] && !reached[i]);
+        match unreached {
+            Some(i) => carve(&mut cliffs, (i % width, i / width), spawn),
+            None => break,
+        }
+    }
+    cliffs
+}
+
+/// Remove cliffs that are not part of any 2x2 block.
+fn prune_cliffs(cliffs: &mut Grid) {
+    loop {
+        let lone: Vec<_> = (0..cliffs.cells.len())
+            .map(|i| (i % cliffs.width, i / cliffs.width))
+            .filter(|&(x, y)| cliffs.get(x as isize, y as isize) && !cliffs.in_block(x, y))
+            .collect();
+        if lone.is_empty() {
+            return;
+        }
+        for (x, y) in lone {
+            cliffs.set(x, y, false);
+        }
+    }
+}
+
+/// Clear an L shaped corridor from `from` to `to`.
+fn carve(cliffs: &mut Grid, from: (usize, usize), to: (usize, usize)) {
+    let (mut x, mut y) = from;
+    cliffs.set(x, y, false);
+    while x != to.0 {
+        x = if x < to.0 { x + 1 } else { x - 1 };
+        cliffs.set(x, y, false);
+    }
+    while y != to.1 {
+        y = if y < to.1 { y + 1 } else { y - 1 };
+        cliffs.set(x, y, false);
+    }
+}
+
+/// The open cell closest to the middle of the grid.
+fn nearest_open(cliffs: &Grid) -> (usize, usize) {
+    let (cx, cy) = (cliffs.width / 2, cliffs.height / 2);
+    (0..cliffs.cells.len())
+        .map(|i| (i % cliffs.width, i / cliffs.width))
+        .filter(|&(x, y)| !cliffs.get(x as isize, y as isize))
+        .min_by_key(|&(x, y)| distance((x, y), (cx, cy)))
+        .unwrap_or((cx, cy))
+}
+
+fn distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Scatter bat spawners away from the spawn and lay dirt paths to them.
+fn place_bat_spawners(map: &mut WorldMap, settings: &Generation, rng: &mut StdRng) {
+    let mut candidates: Vec<_> = (0..map.tiles.len())
+        .map(|i| (i % map.width, i / map.width))
+        .filter(|&(x, y)| {
+            map.tile(x, y) == Tile::Grass && distance((x, y), map.spawn) >= settings.bat_distance
+        })
+        .collect();
+    candidates.shuffle(rng);
+
+    let mut spawners: Vec<(usize, usize)> = Vec::new();
+    for cell in candidates {
+        if spawners.len() == settings.bat_spawners {
+            break;
+        }
+        if spawners
+            .iter()
+            .all(|&other| distance(cell, other) >= BAT_SPACING)
+        {
+            spawners.push(cell);
+        }
+    }
+
+    let parents = path_parents(map);
+    for &(x, y) in &spawners {
+        let mut cell = (x, y);
+        while cell != map.spawn {
+            map.tiles[cell.1 * map.width + cell.0] = Tile::Dirt;
+            cell = match parents[cell.1 * map.width + cell.0] {
+                Some(parent) => parent,
+                None => break,
+            };
+        }
+        map.props.push((x, y, Prop::BatSpawner));
+    }
+    map.tiles[map.spawn.1 * map.width + map.spawn.0] = Tile::Dirt;
+}
+
+/// Breadth first search from the spawn, returning each cell's previous step.
+fn path_parents(map: &WorldMap) -> Vec<Option<(usize, usize)>> {
+    let mut parents = vec![None; map.tiles.len()];
+    let mut queue = VecDeque::from([map.spawn]);
+
+    while let Some((x, y)) = queue.pop_front() {
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            if nx < 0 || ny < 0 || nx >= map.width as isize || ny >= map.height as isize {
+                continue;
+            }
+            let (nx, ny) = (nx as usize, ny as usize);
+            let index = ny * map.width + nx;
+            if map.tile(nx, ny) == Tile::Cliff || parents[index].is_some() || (nx, ny) == map.spawn
+            {
+                continue;
+            }
+            parents[index] = Some((x, y));
+            queue.push_back((nx, ny));
+        }
+    }
+    parents
+}
+
+/// Scatter grass, bushes and trees on free grass cells. Solid props keep a
+/// cell of space around them, trees stay off cliff edges, and a solid prop
+/// that would cut off part of the map is not placed.
+fn place_props(map: &mut WorldMap, settings: &Generation, rng: &mut StdRng) {
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if map.tile(x, y) != Tile::Grass || map.prop(x, y).is_some() {
+                continue;
+            }
+
+            let roll = rng.gen::<f64>();
+            let prop = if roll < settings.tree_density {
+                Prop::Tree
+            } else if roll < settings.tree_density + settings.bush_density {
+                Prop::Bush
+            } else if roll < settings.tree_density + settings.bush_density + settings.grass_density
+            {
+                Prop::Grass
+            } else {
+                continue;
+            };
+
+            if prop.is_solid() {
+                let crowded = around(map, x, y).any(|(nx, ny)| {
+                    map.prop(nx, ny).map_or(false, Prop::is_solid)
+                        || (prop == Prop::Tree && map.tile(nx, ny) == Tile::Cliff)
+                });
+                if crowded {
+                    continue;
+                }
+            }
+
+            map.props.push((x, y, prop));
+            if prop.is_solid() && !map.is_connected() {
+                map.props.pop();
+            }
+        }
+    }
+}
+
+/// The in-bounds cells around `x`, `y`.
+fn around(map: &WorldMap, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (width, height) = (map.width as isize, map.height as isize);
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .map(move |(dx, dy)| (x as isize + dx, y as isize + dy))
+        .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && nx < width && ny < height)
+        .map(|(nx, ny)| (nx as usize, ny as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(seed: u64) -> Generation {
+        Generation {
+            seed,
+            ..Generation::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        assert_eq!(generate(&settings(7)), generate(&settings(7)));
+        assert_ne!(generate(&settings(7)), generate(&settings(8)));
+    }
+
+    #[test]
+    fn cliffs_form_blocks() {
+        for seed in 0..20 {
+            let map = generate(&settings(seed));
+            let mut cliffs = Grid::new(map.width / CLIFF_SCALE, map.height / CLIFF_SCALE);
+            for y in 0..cliffs.height {
+                for x in 0..cliffs.width {
+                    let tile = map.tile(x * CLIFF_SCALE, y * CLIFF_SCALE);
+                    cliffs.set(x, y, tile == Tile::Cliff);
+                }
+            }
+
+            for y in 0..cliffs.height {
+                for x in 0..cliffs.width {
+                    if cliffs.get(x as isize, y as isize) {
+                        assert!(
+                            cliffs.in_block(x, y),
+                            "seed {}: lone cliff at {},{}",
+                            seed,
+                            x,
+                            y
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn everything_reachable() {
+        for seed in 0..20 {
+            let map = generate(&settings(seed));
+            assert!(map.is_walkable(map.spawn.0, map.spawn.1));
+            assert!(map.is_connected(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn bat_spawners_are_spread_out() {
+        let generation = settings(3);
+        let map = generate(&generation);
+        let spawners: Vec<_> = map
+            .props
+            .iter()
+            .filter(|(_, _, prop)| *prop == Prop::BatSpawner)
+            .map(|(x, y, _)| (*x, *y))
+            .collect();
+
+        assert_eq!(spawners.len(), generation.bat_spawners);
+        for &spawner in &spawners {
+            assert!(distance(spawner, map.spawn) >= generation.bat_distance);
+            assert_eq!(map.tile(spawner.0, spawner.1), Tile::Dirt);
+        }
+    }
+}
@@ -2,9 +2,10 @@ use bevy::prelude::{Component, EventReader, Query, With};
 use gdnative::api::TextureRect;
 use gdnative::prelude::*;
 use gdrust::ecs::app::with_world;
-use gdrust::ecs::engine_sync::components::PlayingGame;
 use gdrust::macros::*;
-use gdrust::unsafe_functions::{NodeExt, RefExt};
+
+use crate::bridge::builder::GodotEntity;
+use crate::bridge::lifecycle::SafeRefExt;
 
 pub struct ChangeHealth {
     pub health: i32,
@@ -30,14 +31,14 @@ impl HealthBundle {
     #[export]
     fn _ready(&mut self, owner: TRef<Control>) {
         with_world(|w| {
-            let heart_empty = owner.expect_node::<TextureRect>("HeartUIEmpty").claim();
-            let heart_full = owner.expect_node::<TextureRect>("HeartUIFull").claim();
-
-            w.spawn()
-                .insert(HeartEmpty(heart_empty))
-                .insert(HeartFull(heart_full))
-                .insert(Health)
-                .insert(PlayingGame);
+            GodotEntity::spawn(w, owner)
+                .node("HeartUIEmpty", |heart: TRef<TextureRect>| {
+                    HeartEmpty(heart.claim())
+                })
+                .node("HeartUIFull", |heart: TRef<TextureRect>| {
+                    HeartFull(heart.claim())
+                })
+                .insert(Health);
         });
     }
 }
@@ -47,7 +48,10 @@ pub fn set_health_system(
     mut evnet: EventReader<ChangeHealth>,
 ) {
     for heart_full in health.iter() {
-        let heart_full = heart_full.0.expect_safe();
+        let heart_full = match heart_full.0.try_safe() {
+            Some(heart_full) => heart_full,
+            None => continue,
+        };
 
         for ChangeHealth { health } in evnet.iter() {
             heart_full.set_size(
@@ -63,7 +67,10 @@ pub fn set_max_health_system(
     mut evnet: EventReader<ChangeMaxHealth>,
 ) {
     for heart_empty in health.iter() {
-        let heart_empty = heart_empty.0.expect_safe();
+        let heart_empty = match heart_empty.0.try_safe() {
+            Some(heart_empty) => heart_empty,
+            None => continue,
+        };
 
         for ChangeMaxHealth { max_health } in evnet.iter() {
             heart_empty.set_size(
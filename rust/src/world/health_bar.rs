@@ -0,0 +1,123 @@
+use bevy::prelude::{Commands, Component, Entity, EventReader, Query, Res, Time, Timer};
+use gdnative::api::ColorRect;
+use gdnative::prelude::*;
+use gdrust::macros::*;
+use gdrust::unsafe_functions::NodeExt;
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::combat::Damaged;
+use crate::components::Stats;
+
+/// Health Bar Component.
+/// A world-space bar over an enemy, shown when it is damaged. The chip bar
+/// keeps the previous health for a moment, then drains down to it.
+#[gdrust(extends = Node2D)]
+#[derive(Component, Clone)]
+pub struct HealthBar {
+    #[default(_owner.claim())]
+    pub owner: Ref<Node2D>,
+    pub fill: Option<Ref<ColorRect>>,
+    pub chip: Option<Ref<ColorRect>>,
+    /// Width of a full bar in pixels.
+    #[export]
+    #[default(16.)]
+    pub width: f32,
+    /// Seconds without damage before the bar hides.
+    #[export]
+    #[default(2.)]
+    pub hide_after: f32,
+    /// Seconds before the chip bar starts draining.
+    #[export]
+    #[default(0.4)]
+    pub chip_delay: f32,
+    /// Fraction of a full bar the chip drains per second.
+    #[export]
+    #[default(1.)]
+    pub chip_speed: f32,
+    pub health: f32,
+    pub chip_health: f32,
+}
+
+#[methods]
+impl HealthBar {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Node2D>) {
+        self.fill = Some(owner.expect_node::<ColorRect>("Fill").claim());
+        self.chip = Some(owner.expect_node::<ColorRect>("Chip").claim());
+        self.health = 1.;
+        self.chip_health = 1.;
+        owner.hide();
+    }
+
+    fn draw(&self) {
+        for (rect, fraction) in [(self.fill, self.health), (self.chip, self.chip_health)] {
+            if let Some(rect) = rect.and_then(|rect| rect.try_safe()) {
+                rect.set_size(Vector2::new(self.width * fraction, rect.size().y), false);
+            }
+        }
+    }
+}
+
+/// Showing Health Bar Component.
+/// Only bars with this are animated.
+#[derive(Component)]
+pub struct ShowingHealthBar {
+    hide: Timer,
+    chip: Timer,
+}
+
+/// Health Bar Damage System.
+/// Shows the bar of damaged entities.
+pub fn health_bar_damage_system(
+    mut commands: Commands,
+    mut damaged: EventReader<Damaged>,
+    mut bar: Query<(&Stats, &mut HealthBar)>,
+) {
+    for Damaged { target, .. } in damaged.iter() {
+        let (stats, mut bar) = match bar.get_mut(*target) {
+            Ok(bar) => bar,
+            Err(_) => continue,
+        };
+        // the entity is despawned on death
+        if stats.health <= 0 {
+            continue;
+        }
+
+        bar.health = (stats.health as f32 / stats.max_health.max(1) as f32).clamp(0., 1.);
+        bar.chip_health = bar.chip_health.max(bar.health);
+        bar.draw();
+        if let Some(owner) = bar.owner.try_safe() {
+            owner.show();
+        }
+
+        // restarts both timers if already showing
+        commands.entity(*target).insert(ShowingHealthBar {
+            hide: Timer::from_seconds(bar.hide_after, false),
+            chip: Timer::from_seconds(bar.chip_delay, false),
+        });
+    }
+}
+
+/// Health Bar System.
+/// Drains the chip bar and hides bars once they time out.
+pub fn health_bar_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bar: Query<(Entity, &mut HealthBar, &mut ShowingHealthBar)>,
+) {
+    for (entity, mut bar, mut showing) in bar.iter_mut() {
+        if showing.chip.tick(time.delta()).finished() && bar.chip_health > bar.health {
+            bar.chip_health =
+                (bar.chip_health - bar.chip_speed * time.delta_seconds()).max(bar.health);
+            bar.draw();
+        }
+
+        if showing.hide.tick(time.delta()).finished() {
+            bar.chip_health = bar.health;
+            if let Some(owner) = bar.owner.try_safe() {
+                owner.hide();
+            }
+            commands.entity(entity).remove::<ShowingHealthBar>();
+        }
+    }
+}
@@ -1,11 +1,17 @@
-use crate::world::grass::kill_grass_system;
+use crate::world::checkpoint::{checkpoint_restore_system, checkpoint_system};
+use crate::world::destructible::{destructible_system, regrow_system};
 use crate::world::health::{
     set_health_system, set_max_health_system, ChangeHealth, ChangeMaxHealth,
 };
-use bevy::prelude::{App, Plugin};
+use crate::world::health_bar::{health_bar_damage_system, health_bar_system};
+use bevy::prelude::{App, ParallelSystemDescriptorCoercion, Plugin};
 
-pub mod grass;
+pub mod checkpoint;
+pub mod destructible;
+pub mod generation;
 pub mod health;
+pub mod health_bar;
+pub mod procedural_world;
 pub mod world;
 
 pub struct WorldPlugin;
@@ -13,8 +19,17 @@ impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ChangeHealth>()
             .add_event::<ChangeMaxHealth>()
-            .add_system(kill_grass_system)
+            .add_system(destructible_system.after("node_signal"))
+            .add_system(regrow_system)
+            .add_system(checkpoint_system.after("node_signal").after("save_load"))
+            .add_system(
+                checkpoint_restore_system
+                    .after("save_load")
+                    .after("arrival"),
+            )
             .add_system(set_health_system)
-            .add_system(set_max_health_system);
+            .add_system(set_max_health_system)
+            .add_system(health_bar_damage_system.label("health_bar_damage"))
+            .add_system(health_bar_system.after("health_bar_damage"));
     }
 }
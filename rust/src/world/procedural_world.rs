@@ -0,0 +1,146 @@
+use gdnative::api::TileMap;
+use gdnative::prelude::*;
+use gdrust::macros::*;
+use gdrust::unsafe_functions::{NodeExt, PackedSceneExt, RefExt, ResourceLoaderExt};
+
+use crate::bridge::lifecycle::SafeRefExt;
+use crate::world::generation::{self, Generation, Prop, Tile, WorldMap, CLIFF_SCALE};
+
+/// Size of a map cell in pixels.
+const CELL_SIZE: f32 = 16.;
+
+/// Procedural World.
+/// Generates the overworld into the tile maps and y-sort named by its paths,
+/// and moves the player to the generated spawn.
+#[gdrust(extends = Node2D)]
+#[derive(Clone)]
+pub struct ProceduralWorld {
+    #[export]
+    pub seed: i64,
+    #[export]
+    #[default(40)]
+    pub width: i64,
+    #[export]
+    #[default(24)]
+    pub height: i64,
+    #[export]
+    #[default(0.35)]
+    pub cliff_density: f64,
+    #[export]
+    #[default(0.08)]
+    pub grass_density: f64,
+    #[export]
+    #[default(0.03)]
+    pub bush_density: f64,
+    #[export]
+    #[default(0.02)]
+    pub tree_density: f64,
+    #[export]
+    #[default(4)]
+    pub bat_spawners: i64,
+    #[export]
+    #[default("../DirtPathTileMap".to_string())]
+    pub dirt_path: String,
+    #[export]
+    #[default("../DirtCliftTileMap".to_string())]
+    pub cliff_path: String,
+    #[export]
+    #[default("../YSort".to_string())]
+    pub props_path: String,
+    #[export]
+    #[default("../YSort/Player".to_string())]
+    pub player_path: String,
+}
+
+#[methods]
+impl ProceduralWorld {
+    #[export]
+    fn _ready(&mut self, owner: TRef<Node2D>) {
+        // the siblings are still being set up
+        unsafe { owner.call_deferred("generate", &[]) };
+    }
+
+    #[export]
+    fn generate(&mut self, owner: TRef<Node2D>) {
+        let map = generation::generate(&Generation {
+            seed: self.seed as u64,
+            width: self.width.max(0) as usize,
+            height: self.height.max(0) as usize,
+            cliff_density: self.cliff_density,
+            grass_density: self.grass_density,
+            bush_density: self.bush_density,
+            tree_density: self.tree_density,
+            bat_spawners: self.bat_spawners.max(0) as usize,
+            ..Generation::default()
+        });
+
+        fill_dirt(owner.expect_node::<TileMap>(self.dirt_path.as_str()), &map);
+        fill_cliffs(owner.expect_node::<TileMap>(self.cliff_path.as_str()), &map);
+        add_props(owner.expect_node::<Node>(self.props_path.as_str()), &map);
+
+        if let Some(player) = owner
+            .get_node(self.player_path.as_str())
+            .and_then(|player| player.try_safe())
+            .and_then(|player| player.cast::<Node2D>())
+        {
+            player.set_position(cell_center(map.spawn.0, map.spawn.1));
+        }
+    }
+}
+
+fn cell_center(x: usize, y: usize) -> Vector2 {
+    Vector2::new((x as f32 + 0.5) * CELL_SIZE, (y as f32 + 0.5) * CELL_SIZE)
+}
+
+fn fill_dirt(tile_map: TRef<TileMap>, map: &WorldMap) {
+    tile_map.clear();
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if map.tile(x, y) == Tile::Dirt {
+                tile_map.set_cell(x as i64, y as i64, 0, false, false, false, Vector2::ZERO);
+            }
+        }
+    }
+    tile_map.update_bitmask_region(Vector2::ZERO, Vector2::ZERO);
+}
+
+/// Cliffs sit on a coarser grid. A ring of cliffs is added around the map so
+/// its edge autotiles as solid rock.
+fn fill_cliffs(tile_map: TRef<TileMap>, map: &WorldMap) {
+    let width = (map.width / CLIFF_SCALE) as i64;
+    let height = (map.height / CLIFF_SCALE) as i64;
+
+    tile_map.clear();
+    for y in -1..=height {
+        for x in -1..=width {
+            let outside = x < 0 || y < 0 || x == width || y == height;
+            if outside
+                || map.tile(x as usize * CLIFF_SCALE, y as usize * CLIFF_SCALE) == Tile::Cliff
+            {
+                tile_map.set_cell(x, y, 0, false, false, false, Vector2::ZERO);
+            }
+        }
+    }
+    tile_map.update_bitmask_region(Vector2::ZERO, Vector2::ZERO);
+}
+
+fn add_props(parent: TRef<Node>, map: &WorldMap) {
+    let loader = ResourceLoader::godot_singleton();
+    let grass = loader.expect_load_scene("res://scenes/world/Grass.tscn");
+    let bush = loader.expect_load_scene("res://scenes/world/Bush.tscn");
+    let tree = loader.expect_load_scene("res://scenes/world/Tree.tscn");
+    let bat = loader.expect_load_scene("res://scenes/enemies/Bat.tscn");
+
+    for &(x, y, prop) in &map.props {
+        let scene = match prop {
+            Prop::Grass => &grass,
+            Prop::Bush => &bush,
+            Prop::Tree => &tree,
+            Prop::BatSpawner => &bat,
+        };
+
+        let node = scene.expect_safe().expect_instance_as::<Node2D>();
+        node.set_position(cell_center(x, y));
+        parent.add_child(node, false);
+    }
+}
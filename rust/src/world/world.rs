@@ -1,11 +1,15 @@
+use bevy::app::Events;
 use bevy::prelude::{DespawnRecursiveExt, Entity};
 use gdnative::api::MainLoop;
 use gdnative::prelude::*;
 use gdrust::ecs::app::with_world;
-use gdrust::ecs::engine_sync::components::{GameNode, GodotObjRef, PlayingGame};
+use gdrust::ecs::engine_sync::components::{GameNode, GodotObjRef};
 use gdrust::ecs::engine_sync::events::spawn_game;
 use gdrust::macros::*;
 
+use crate::bridge::builder::GodotEntity;
+use crate::time_scale::SlowMotion;
+
 #[gdrust(extends = Node2D)]
 #[derive(Default, Clone)]
 pub struct WorldBundle {
@@ -17,22 +21,33 @@ impl WorldBundle {
     #[export]
     fn _ready(&mut self, owner: TRef<Node2D>) {
         with_world(|w| {
-            let entity = w
-                .spawn()
+            let entity = GodotEntity::spawn(w, owner)
                 .insert(GodotObjRef::new(owner.upcast::<Node>().claim()))
                 .insert(GameNode)
-                .insert(PlayingGame)
                 .id();
             self.entity = Some(entity);
             spawn_game(w, entity);
         });
     }
 
+    /// Slow the whole game down to `scale` for `duration` real seconds.
+    #[export]
+    fn slow_motion(&self, _owner: TRef<Node2D>, scale: f32, duration: f32) {
+        with_world(|w| {
+            if let Some(mut events) = w.get_resource_mut::<Events<SlowMotion>>() {
+                events.send(SlowMotion { scale, duration });
+            }
+        });
+    }
+
     #[export]
     fn _notification(&mut self, _owner: TRef<Node2D>, what: i64) {
         if what == 1 || what == MainLoop::NOTIFICATION_WM_GO_BACK_REQUEST {
             with_world(|w| {
-                w.entity_mut(self.entity.unwrap()).despawn_recursive();
+                // the world may already be gone if it left the tree first
+                if let Some(entity) = self.entity.and_then(|entity| w.get_entity_mut(entity)) {
+                    entity.despawn_recursive();
+                }
             });
         }
     }